@@ -16,13 +16,22 @@ fn main() {
         .args_from_usage(
             "--format=<FORMAT> 'Sets the output format'
              <INPUT>           'Sets the input file to use'
-             --debug           'Enables debug output'",
+             --debug           'Enables debug output'
+             --listing=[FILE]  'Writes an assembly listing to FILE'
+             --symbols=[FILE]  'Writes the symbols and line numbers to FILE, for stir'
+             --byte-size=[SIZE] 'Sets the byte size, from 64 (binary) to 100 (decimal)'",
         )
         .get_matches();
 
     let format = matches.value_of("format").unwrap();
     let filename = matches.value_of("INPUT").unwrap();
     let debug = matches.is_present("debug");
+    let listing = matches.value_of("listing");
+    let symbols = matches.value_of("symbols");
+    let byte_size = match matches.value_of("byte-size") {
+        Some(byte_size) => byte_size.parse().unwrap(),
+        None => mix::BINARY_BYTE_SIZE,
+    };
 
     eprintln!("===SHAKE===");
 
@@ -33,15 +42,30 @@ fn main() {
     if debug {
         lex(input);
     }
-    assemble(input, format, debug);
+    assemble(input, format, debug, listing, symbols, byte_size);
 }
 
-fn assemble(input: &String, format: &str, debug: bool) {
+fn assemble(
+    input: &String,
+    format: &str,
+    debug: bool,
+    listing: Option<&str>,
+    symbols: Option<&str>,
+    byte_size: isize,
+) {
     let program = parse(input);
 
-    let mut assembler = mixal::Assembler::new();
+    let mut assembler = mixal::Assembler::with_byte_size(byte_size).unwrap();
     assembler.assemble(program).unwrap();
 
+    if let Some(listing) = listing {
+        std::fs::write(listing, assembler.listing(input)).unwrap();
+    }
+
+    if let Some(symbols) = symbols {
+        assembler.debug_info().save(symbols).unwrap();
+    }
+
     if debug {
         let mut words: Vec<(&usize, &mix::Word)> = assembler.words.iter().collect();
         words.sort_by_key(|x| x.0);
@@ -72,7 +96,7 @@ fn assemble(input: &String, format: &str, debug: bool) {
                 write!(card, "SHAKE{}{:04}", group.len(), group[0]).unwrap();
                 for location in group {
                     let word = assembler.words[location];
-                    let value = word.value();
+                    let value = word.value_in(byte_size);
                     if value >= 0 {
                         write!(card, "{:010}", value).unwrap();
                     } else {
@@ -1,14 +1,21 @@
+use std::fs;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufReader, BufWriter, Read};
+use std::path::PathBuf;
+use std::time::Duration;
 
 use std::collections::HashMap;
 
 use bincode;
 
-use clap::App;
+use clap::{App, Arg};
 
 use negroni::computer;
+use negroni::debugger::{self, Command, Debugger};
+use negroni::io;
 use negroni::mix;
+use negroni::mix::disasm;
+use negroni::mixal;
 
 fn main() {
     let matches = App::new("stir")
@@ -16,35 +23,136 @@ fn main() {
         .author("Jonny Stoten <jonny@jonnystoten.com>")
         .about("Emulator for MIX")
         .args_from_usage(
-            "--format=<FORMAT>    'Sets the input format'
+            "--format=[FORMAT]    'Sets the input format'
              [INPUT]              'Sets the input file to use'
              --interactive        'Enables interactive debugger'
-             --break=[BREAKPOINT] 'Specifies a PC to break on'",
+             --history=[STEPS]    'Sets how many steps the debugger can go back (default 1000)'
+             --break=[BREAKPOINT] 'Specifies a PC to break on'
+             --profile=[REPORT]   'Writes an execution profile to REPORT'
+             --listing=[LISTING]  'Annotates the profile with a listing from shake'
+             --byte-size=[SIZE]   'Sets the byte size, from 64 (binary) to 100 (decimal)'
+             --interrupts         'Enables the interrupt facility, starting in control state'
+             --save-snapshot=[FILE] 'Saves the state of the machine to FILE when it stops'
+             --trace=[FILE]       'Writes a line to FILE for each instruction executed'
+             --trace-format=[FORMAT] 'Sets the trace format: text (default) or jsonl'
+             --disassemble        'Prints the program as MIXAL instead of running it'
+             --symbols=[FILE]     'Loads symbols from shake, to use in traces, the debugger and disassembly'
+             --gdb=[PORT]         'Waits for GDB to connect on PORT on localhost, to debug with it'
+             --dap                'Speaks the Debug Adapter Protocol on stdin and stdout, for editors'
+             --max-instructions=[COUNT] 'Stops after running COUNT instructions, with exit status 2'
+             --max-time=[UNITS]   'Stops once the clock reaches UNITS, with exit status 3'
+             --timeout=[SECONDS]  'Stops after SECONDS of real time, with exit status 4'
+             --max-output=[BLOCKS] 'Stops before writing more than BLOCKS to any unit, with exit status 5'
+             --synchronous-io     'Finishes each I/O transfer straight away, so that runs are reproducible'
+             --io-dir=[DIR]       'Keeps the device files in DIR instead of a temporary one'
+             --devices=[FILE]     'Reads which file each unit uses from a TOML file'",
+        )
+        .arg(
+            Arg::from_usage(
+                "--unit=[MAPPING]... 'Attaches a unit to a file, to stdin or stdout with -, or to nothing with none, e.g. 16=deck.txt'",
+            )
+            .number_of_values(1),
         )
         .get_matches();
 
-
     let interactive = matches.is_present("interactive");
-    let format = matches.value_of("format").unwrap();
     let breakpoint = matches.value_of("break");
-    let breakpoint: Option<usize> = match breakpoint {
-        Some(b) => Some(b.parse().unwrap()),
-        None => None
+
+    let gdb: Option<u16> = matches.value_of("gdb").map(|port| port.parse().unwrap());
+
+    let history: usize = match matches.value_of("history") {
+        Some(h) => h.parse().unwrap(),
+        None => 1000,
+    };
+
+    let profile = matches.value_of("profile");
+    let listing = matches.value_of("listing");
+    let byte_size = match matches.value_of("byte-size") {
+        Some(byte_size) => byte_size.parse().unwrap(),
+        None => mix::BINARY_BYTE_SIZE,
+    };
+
+    if matches.is_present("dap") {
+        // the editor launches a .mixal file itself, so there's no input here
+        let stdin = BufReader::new(std::io::stdin());
+        debugger::dap::serve(stdin, std::io::stdout(), byte_size).unwrap();
+        return;
+    }
+
+    let format = matches.value_of("format").expect("--format is required");
+    let save_snapshot = matches.value_of("save-snapshot");
+
+    let limits = computer::Limits {
+        instructions: matches
+            .value_of("max-instructions")
+            .map(|n| n.parse().unwrap()),
+        time: matches.value_of("max-time").map(|n| n.parse().unwrap()),
+        wall_clock: matches
+            .value_of("timeout")
+            .map(|n| Duration::from_secs_f64(n.parse().unwrap())),
+        output: matches.value_of("max-output").map(|n| n.parse().unwrap()),
     };
 
-    let mut computer = computer::Computer::new();
+    let debug_info = match matches.value_of("symbols") {
+        Some(path) => mixal::DebugInfo::load(path).unwrap(),
+        None => mixal::DebugInfo::default(),
+    };
+
+    let trace = matches.value_of("trace");
+    let trace_format = match matches.value_of("trace-format") {
+        Some(f) => computer::TraceFormat::parse(f).expect("unknown trace format"),
+        None => computer::TraceFormat::Text,
+    };
+
+    let io_mode = if matches.is_present("synchronous-io") {
+        io::IoMode::Synchronous
+    } else {
+        io::IoMode::Threaded
+    };
+
+    let mut devices = match matches.value_of("devices") {
+        Some(path) => io::DeviceConfig::load(path).unwrap(),
+        None => io::DeviceConfig::default(),
+    };
+    if let Some(directory) = matches.value_of("io-dir") {
+        devices.directory = Some(PathBuf::from(directory));
+    }
+    for mapping in matches.values_of("unit").into_iter().flatten() {
+        devices.add_mapping(mapping).unwrap();
+    }
+
+    let snapshot = match format {
+        "snapshot" => {
+            let input = matches.value_of("INPUT").expect("no snapshot file given");
+            Some(computer::Snapshot::load(input).unwrap())
+        }
+        _ => None,
+    };
+
+    // a snapshot knows what machine it came from
+    let byte_size = match &snapshot {
+        Some(snapshot) => snapshot.byte_size,
+        None => byte_size,
+    };
+    let builder = computer::ComputerBuilder::new()
+        .byte_size(byte_size)
+        .io_mode(io_mode);
+    let mut computer = devices.apply(builder).unwrap().build().unwrap();
+    if let Some(directory) = computer.temporary_directory() {
+        eprintln!(
+            "Device files are in {}, until stir exits",
+            directory.display()
+        );
+    }
+    if let Some(snapshot) = &snapshot {
+        computer.restore(snapshot).unwrap();
+    }
 
     match format {
         "binary" => {
             let mut input_file = File::open("out.bin").unwrap();
-            let size = {
-                let result = input_file.seek(SeekFrom::End(0)).unwrap();
-                input_file.seek(SeekFrom::Start(0)).unwrap();
-                result
-            };
-
-            let mut buffer = vec![0; size as usize];
-            input_file.read(&mut buffer).unwrap();
+            let mut buffer = Vec::new();
+            input_file.read_to_end(&mut buffer).unwrap();
             let (words, program_start): (HashMap<usize, mix::Word>, usize) =
                 bincode::deserialize(&buffer).unwrap();
 
@@ -53,7 +161,7 @@ fn main() {
             }
 
             eprintln!("Setting PC to {}", program_start);
-            computer.program_counter = program_start;
+            computer.program_counter = program_start as isize;
         }
         "deck" => {
             let instruction = mix::Instruction {
@@ -62,50 +170,173 @@ fn main() {
                 address: mix::Address::zero(),
                 index_specification: 0,
             };
-            let operation = instruction.decode();
-            operation.execute(&mut computer);
-            for io_device in &computer.io_devices {
-                io_device.wait_ready();
+            let operation = instruction.decode().unwrap();
+            let loaded = operation
+                .execute(&mut computer)
+                .and_then(|_| computer.wait_for_devices());
+            if let Err(fault) = loaded {
+                eprintln!("FAULT: {}", fault);
+                drop(computer);
+                std::process::exit(1);
             }
+            // the GO button waits until the card reader has finished with the
+            // card, as the loader is about to run from it
+            computer.clock = computer.io_devices[16].ready_at();
             computer.program_counter = 0;
             computer.jump_address = mix::Address::zero();
         }
+        "snapshot" => {
+            eprintln!("Resuming at {}", computer.program_counter);
+        }
         _ => panic!("unknown format"),
     }
 
+    if matches.is_present("disassemble") {
+        disassemble(&computer, &debug_info);
+        return;
+    }
 
-    if interactive {
-        let mut waiting_for_break = match breakpoint {
-            Some(_) => true,
-            None => false,
-        };
-        computer.start_interactive(|computer| {
-            if computer.program_counter < 100 {
-                // don't step through the loader
-                return;
+    computer.limits = limits;
+    if profile.is_some() {
+        computer.enable_profiling();
+    }
+    if matches.is_present("interrupts") && computer.interrupts.is_none() {
+        computer.enable_interrupts();
+    }
+    if let Some(path) = trace {
+        let output = BufWriter::new(File::create(path).unwrap());
+        let trace =
+            computer::Trace::new(output, trace_format).with_labels(debug_info.symbols.clone());
+        computer.add_observer(Box::new(trace));
+    }
+
+    let result = if let Some(port) = gdb {
+        computer.enable_history(history);
+        eprintln!("Waiting for GDB on localhost:{}", port);
+        let mut debugger = Debugger::new();
+        debugger::gdb::serve(&mut computer, &mut debugger, port).unwrap();
+        Ok(())
+    } else if interactive {
+        computer.enable_history(history);
+        let mut debugger = Debugger::new();
+        debugger.symbols = debug_info.symbols;
+        debugger.lines = debug_info.lines;
+        if let Some(address) = breakpoint {
+            let result = debugger
+                .parse(&format!("break {}", address))
+                .and_then(|command| debugger.execute(&mut computer, &command));
+            if let Err(message) = result {
+                eprintln!("--break: {}", message);
+                drop(computer);
+                std::process::exit(1);
             }
+        }
+        debug(&mut computer, debugger)
+    } else {
+        computer.start()
+    };
+    let result = result.and(computer.wait_for_devices());
+    // this flushes the trace
+    let observed = computer.finish_observers();
+    if let Err(message) = &observed {
+        eprintln!("{}", message);
+    }
+
+    eprintln!("===MIX COMPUTER===");
+    eprintln!("{:?}", computer);
+    eprintln!("Total time: {}u", computer.clock);
+
+    if let Some(path) = save_snapshot {
+        computer.snapshot().save(path).unwrap();
+    }
+
+    if let (Some(report), Some(profile)) = (profile, &computer.profile) {
+        let listing = listing.map(|l| fs::read_to_string(l).unwrap());
+        fs::write(report, profile.report(listing.as_deref())).unwrap();
+    }
+
+    if let Err(fault) = result {
+        eprintln!("FAULT: {}", fault);
+        let code = match fault {
+            computer::MixFault::LimitExceeded { limit, .. } => limit.code(),
+            _ => 1,
+        };
+        // exiting skips the drop that removes a temporary I/O directory
+        drop(computer);
+        std::process::exit(code);
+    }
+    if observed.is_err() {
+        drop(computer);
+        std::process::exit(1);
+    }
+}
 
-            if waiting_for_break {
-                if computer.program_counter != breakpoint.unwrap() {
-                    return;
-                } else {
-                    waiting_for_break = false;
+fn disassemble(computer: &computer::Computer, debug_info: &mixal::DebugInfo) {
+    let symbols = &debug_info.symbols;
+
+    // empty memory is all NOPs, so leave it out
+    for (address, cell) in computer.memory.iter().enumerate() {
+        let word = cell.read();
+        if word == mix::Word::zero() {
+            continue;
+        }
+
+        let address = address as isize;
+        let text =
+            disasm::to_mixal_with_labels(word, Some(address), Some(symbols), computer.byte_size);
+        if symbols.is_empty() {
+            println!("{:04}  {}", address, text);
+        } else {
+            let label = symbols
+                .iter()
+                .filter(|(_, value)| **value == address)
+                .map(|(name, _)| name.as_str())
+                .min()
+                .unwrap_or("");
+            println!("{:04}  {:<10} {}", address, label, text);
+        }
+    }
+}
+
+fn debug(
+    computer: &mut computer::Computer,
+    mut debugger: Debugger,
+) -> Result<(), computer::MixFault> {
+    eprintln!("{}", debugger.execute(computer, &Command::Step(0)).unwrap());
+
+    // an empty line repeats the last command
+    let mut last = Command::Step(1);
+    loop {
+        eprint!("(stir) ");
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+
+        let command = if line.trim().is_empty() {
+            last.clone()
+        } else {
+            match debugger.parse(&line) {
+                Ok(command) => command,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    continue;
                 }
             }
+        };
+        if command == Command::Quit {
+            break;
+        }
 
-            eprintln!("===MIX COMPUTER===");
-            eprintln!("{:?}", computer);
-            let mut stdin = std::io::stdin();
-            stdin.read(&mut [0u8]).unwrap();
-        });
-    } else {
-        computer.start();
+        match debugger.execute(computer, &command) {
+            Ok(output) => eprintln!("{}", output),
+            Err(message) => eprintln!("{}", message),
+        }
+        last = command;
     }
 
-    for io_device in &computer.io_devices {
-        io_device.wait_ready();
+    match debugger.fault {
+        Some(fault) => Err(fault),
+        None => Ok(()),
     }
-
-    eprintln!("===MIX COMPUTER===");
-    eprintln!("{:?}", computer);
 }
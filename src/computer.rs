@@ -1,16 +1,34 @@
-
 use std::fmt;
-use std::fs;
+use std::path::Path;
 
-use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::RwLock;
 
-use dirs;
-
 use crate::io;
 use crate::mix;
 
+mod builder;
+mod fault;
+mod history;
+mod interrupt;
+mod limits;
+mod profile;
+mod snapshot;
+mod trace;
+mod watch;
+
+pub use builder::ComputerBuilder;
+pub use fault::MixFault;
+pub use history::History;
+pub use interrupt::{Interrupts, INT_INTERRUPT};
+pub use limits::{Limit, Limits};
+pub use profile::Profile;
+pub use snapshot::{ControlState, Snapshot};
+pub use trace::{json_line, text_line, Observer, Trace, TraceEntry, TraceFormat};
+pub use watch::{Access, WatchHit, WatchKind, Watches, Watchpoint};
+
+use builder::TemporaryDirectory;
+
 pub struct MemoryCell {
   lock: RwLock<mix::Word>,
 }
@@ -22,30 +40,34 @@ impl MemoryCell {
     }
   }
 
+  /// Reads the word, waiting for a device if it's in the middle of a
+  /// transfer.
   pub fn read(&self) -> mix::Word {
-    *self.lock.try_read().unwrap()
+    *self.lock.read().unwrap()
   }
 
   pub fn write(&self, word: mix::Word) {
-    let mut mem = self.lock.try_write().unwrap();
+    let mut mem = self.lock.write().unwrap();
     *mem = word;
   }
-}
 
-fn make_io_path(filename: &str) -> PathBuf {
-  let home = dirs::home_dir().unwrap();
-  let mut io_dir = home.join(".negroni/io");
-  if cfg!(test) {
-    io_dir = io_dir.join("test");
+  pub fn try_read(&self) -> Option<mix::Word> {
+    self.lock.try_read().ok().map(|word| *word)
   }
-  fs::create_dir_all(&io_dir).unwrap();
 
-  io_dir.join(filename)
+  pub fn try_write(&self, word: mix::Word) -> Option<()> {
+    let mut mem = self.lock.try_write().ok()?;
+    *mem = word;
+    Some(())
+  }
 }
 
 pub struct Computer {
+  // the number of values a byte can hold, from 64 for a binary MIX to 100
+  // for a decimal one
+  pub byte_size: isize,
   pub running: bool,
-  pub program_counter: usize,
+  pub program_counter: isize,
   pub accumulator: mix::Word,
   pub extension: Arc<MemoryCell>,
   pub indexes: [mix::Address; 6],
@@ -54,120 +76,378 @@ pub struct Computer {
   pub overflow: bool,
   pub comparison: mix::Comparison,
   pub io_devices: Vec<io::IoDevice>,
+  pub clock: usize,
+  pub profile: Option<Profile>,
+  pub interrupts: Option<Interrupts>,
+  pub history: Option<History>,
+  pub watches: Arc<Watches>,
+  pub observers: Vec<Box<dyn Observer>>,
+  pub limits: Limits,
+  usage: limits::Usage,
+  // locations written by the current instruction, for the observers
+  written: Vec<isize>,
+  // whether the current instruction took a jump, for the profile
+  jumped: bool,
+  // where the device files are when the builder wasn't given a directory
+  temporary_directory: Option<TemporaryDirectory>,
 }
 
 impl Computer {
   pub fn new() -> Computer {
-    let raw_memory = [mix::Word {
-      bytes: [0, 0, 0, 0, 0],
-      sign: mix::Sign::Positive,
-    }; 4000];
-
-    let memory: Vec<MemoryCell> = raw_memory.iter().map(|x| MemoryCell::new(*x)).collect();
-    let memory = Arc::new(memory);
-
-    let indexes = [mix::Address {
-      bytes: [0, 0],
-      sign: mix::Sign::Positive,
-    }; 6];
-
-    let mut io_devices: Vec<io::IoDevice> = Vec::with_capacity(21);
-    for i in 0..8 {
-      io_devices.push(io::TapeUnit::new(
-        make_io_path(&format!("tape{}.dat", i)).to_str().unwrap(),
-      ));
-    }
-    for i in 8..16 {
-      io_devices.push(io::DiskUnit::new(
-        make_io_path(&format!("disk{}.dat", i)).to_str().unwrap(),
-      ));
-    }
-
-    io_devices.push(io::CardReader::new(
-      make_io_path("card_reader.dat").to_str().unwrap(),
-    ));
-    io_devices.push(io::CardPunch::new(
-      make_io_path("card_punch.dat").to_str().unwrap(),
-    ));
-    io_devices.push(io::LinePrinter::new(
-      make_io_path("line_printer.dat").to_str().unwrap(),
-    ));
-
-    let computer = Computer {
-      running: false,
-      program_counter: 0,
-      accumulator: mix::Word {
-        bytes: [0, 0, 0, 0, 0],
-        sign: mix::Sign::Positive,
-      },
-      extension: Arc::new(MemoryCell::new(mix::Word {
-        bytes: [0, 0, 0, 0, 0],
-        sign: mix::Sign::Positive,
-      })),
-      indexes,
-      jump_address: mix::Address {
-        bytes: [0, 0],
-        sign: mix::Sign::Positive,
-      },
-      memory,
-      overflow: false,
-      comparison: mix::Comparison::Equal,
-      io_devices,
-    };
+    Computer::with_io_mode(io::IoMode::Threaded)
+  }
 
-    for io in computer.io_devices.iter() {
-      io.start(&computer);
-    }
+  /// A computer whose devices all work in `io_mode`.
+  pub fn with_io_mode(io_mode: io::IoMode) -> Computer {
+    ComputerBuilder::new().io_mode(io_mode).build().unwrap()
+  }
 
-    computer
+  /// The directory that the builder made for the device files, if it
+  /// wasn't given one. It's removed when the computer is dropped.
+  pub fn temporary_directory(&self) -> Option<&Path> {
+    self.temporary_directory.as_ref().map(|d| d.path())
   }
 
-  pub fn start(&mut self) -> () {
-    self.start_interactive(|_| {});
+  pub fn start(&mut self) -> Result<(), MixFault> {
+    self.start_interactive(|_| {})
   }
 
-  pub fn start_interactive<F>(&mut self, mut f: F)
+  pub fn start_interactive<F>(&mut self, mut f: F) -> Result<(), MixFault>
   where
     F: FnMut(&mut Self) -> (),
   {
     self.running = true;
+    self.start_wall_clock();
     while self.running {
       f(self);
-      self.fetch_decode_execute();
-      if self.program_counter >= self.memory.len() {
+      self.step()?;
+    }
+
+    Ok(())
+  }
+
+  /// Runs a single instruction, stopping the machine if that leaves the
+  /// program counter outside of memory.
+  pub fn step(&mut self) -> Result<(), MixFault> {
+    self.fetch_decode_execute()?;
+    if !self.is_valid_address(self.program_counter) {
+      self.running = false;
+    }
+
+    Ok(())
+  }
+
+  pub fn enable_profiling(&mut self) {
+    self.profile = Some(Profile::new(self.memory.len()));
+  }
+
+  pub fn fetch_decode_execute(&mut self) -> Result<(), MixFault> {
+    let fetched = self
+      .fetch()
+      .and_then(|instruction| self.check_limits(&instruction).map(|_| instruction));
+    let instruction = match fetched {
+      Ok(instruction) => instruction,
+      Err(fault) => {
+        // nothing has happened yet, so the machine can carry on from here
+        // once whatever stopped it is fixed, such as by raising the limits
+        self.running = false;
+        return Err(fault);
+      }
+    };
+
+    self.record_step();
+
+    let location = self.program_counter;
+    self.jumped = false;
+    let effective_address = if self.is_observed() {
+      self.get_indexed_address_value(&instruction).ok()
+    } else {
+      None
+    };
+
+    let result = match instruction.decode() {
+      Some(operation) => operation.execute(self).map(|_| {
+        (
+          operation.time(),
+          operation.should_increment_program_counter(),
+        )
+      }),
+      None => Err(self.invalid_instruction(&instruction)),
+    };
+
+    let time = match result {
+      Ok((time, true)) => {
+        self.program_counter += 1;
+        time
+      }
+      Ok((time, false)) => time,
+      Err(fault) => {
         self.running = false;
+        self.written.clear();
+        return Err(fault);
+      }
+    };
+
+    self.clock += time;
+    self.record_usage(&instruction);
+    if let Some(profile) = self.profile.as_mut() {
+      if location >= 0 {
+        profile.record(location as usize, time, self.jumped);
       }
     }
+
+    if self.is_observed() {
+      self.notify_observers(location, &instruction, effective_address);
+    }
+
+    self.check_interrupts(time);
+
+    Ok(())
+  }
+
+  /// Called by the jump instructions when they take the jump, which may be
+  /// to the next location anyway.
+  pub(crate) fn record_jump(&mut self) {
+    self.jumped = true;
+  }
+
+  fn fetch(&self) -> Result<mix::Instruction, MixFault> {
+    let cell = match self.memory_cell(self.program_counter) {
+      Some(cell) if self.is_valid_address(self.program_counter) => cell,
+      _ => {
+        // there's no instruction to blame, as there's nothing to fetch
+        let instruction = mix::Instruction::from_word(mix::Word::zero());
+        return Err(self.address_out_of_range(&instruction, self.program_counter));
+      }
+    };
+    let instruction = mix::Instruction::from_word(cell.read());
+    self.check_buffers(&instruction, self.program_counter, Access::Read)?;
+
+    Ok(instruction)
   }
 
-  pub fn fetch_decode_execute(&mut self) -> () {
-    let instruction = self.fetch();
-    let operation = instruction.decode();
-    operation.execute(self);
-    if operation.should_increment_program_counter() {
-      self.program_counter += 1;
+  /// The cell at `address`, which is one of the interrupt facility's
+  /// locations if it's negative.
+  pub(crate) fn memory_cell(&self, address: isize) -> Option<&MemoryCell> {
+    if address < 0 {
+      let interrupts = self.interrupts.as_ref()?;
+      if -address as usize > interrupts.memory.len() {
+        return None;
+      }
+      Some(interrupts.cell(address))
+    } else {
+      self.memory.get(address as usize)
     }
   }
 
-  fn fetch(&self) -> mix::Instruction {
-    let word = self.memory[self.program_counter].read();
+  // negative locations can only be used in control state
+  pub(crate) fn lowest_address(&self) -> isize {
+    match &self.interrupts {
+      Some(interrupts) if interrupts.control_state => -(interrupts.memory.len() as isize),
+      _ => 0,
+    }
+  }
+
+  pub(crate) fn is_valid_address(&self, address: isize) -> bool {
+    address >= self.lowest_address() && address < self.memory.len() as isize
+  }
+
+  pub fn wait_for_devices(&self) -> Result<(), MixFault> {
+    let deadline = self.deadline();
+    for io in self.io_devices.iter() {
+      if !io.wait_ready_until(deadline) {
+        // the instruction that started the transfer has been and gone
+        let instruction = mix::Instruction::from_word(mix::Word::zero());
+        return Err(self.wall_clock_exceeded(&instruction));
+      }
+      io.check_fault()?;
+    }
 
-    mix::Instruction::from_word(word)
+    Ok(())
   }
 
-  pub fn get_indexed_address_value(&self, instruction: &mix::Instruction) -> isize {
+  pub fn get_indexed_address_value(
+    &self,
+    instruction: &mix::Instruction,
+  ) -> Result<isize, MixFault> {
     let index = instruction.index_specification as usize;
     if index > 6 {
-      panic!("index spec out of range: {}", index);
+      return Err(MixFault::InvalidIndex {
+        program_counter: self.program_counter,
+        instruction: *instruction,
+      });
     }
 
-    let value = instruction.address.value();
+    let value = instruction.address.value_in(self.byte_size);
     if index == 0 {
-      return value;
+      return Ok(value);
+    }
+
+    let index_value = self.indexes[index - 1].value_in(self.byte_size);
+    Ok(value + index_value)
+  }
+
+  pub fn get_io_device(&self, instruction: &mix::Instruction) -> Result<&io::IoDevice, MixFault> {
+    self
+      .io_devices
+      .get(instruction.modification as usize)
+      .ok_or(self.invalid_instruction(instruction))
+  }
+
+  pub fn invalid_instruction(&self, instruction: &mix::Instruction) -> MixFault {
+    MixFault::InvalidInstruction {
+      program_counter: self.program_counter,
+      instruction: *instruction,
+    }
+  }
+
+  pub fn get_memory_address(&self, instruction: &mix::Instruction) -> Result<isize, MixFault> {
+    let address = self.get_indexed_address_value(instruction)?;
+    self.check_memory_range(instruction, address, 1)?;
+
+    Ok(address)
+  }
+
+  pub fn address_out_of_range(&self, instruction: &mix::Instruction, address: isize) -> MixFault {
+    MixFault::AddressOutOfRange {
+      program_counter: self.program_counter,
+      instruction: *instruction,
+      address,
+    }
+  }
+
+  pub fn check_memory_range(
+    &self,
+    instruction: &mix::Instruction,
+    address: isize,
+    length: usize,
+  ) -> Result<(), MixFault> {
+    if address < self.lowest_address() || address + length as isize > self.memory.len() as isize {
+      return Err(self.address_out_of_range(instruction, address));
+    }
+
+    Ok(())
+  }
+
+  /// Faults if `address` is part of a buffer that a busy device is reading
+  /// into, or writing out from. Reading a buffer that's being written out is
+  /// fine, as it can't change.
+  pub fn check_buffers(
+    &self,
+    instruction: &mix::Instruction,
+    address: isize,
+    access: Access,
+  ) -> Result<(), MixFault> {
+    for (unit, device) in self.io_devices.iter().enumerate() {
+      let (operation, buffer) = match device.buffer() {
+        Some(buffer) => buffer,
+        None => continue,
+      };
+
+      let conflict = operation == mix::op_codes::IN || access == Access::Write;
+      if conflict && buffer.contains(&address) && device.busy(self.clock) {
+        return Err(MixFault::BufferInUse {
+          program_counter: self.program_counter,
+          instruction: *instruction,
+          address,
+          unit: unit as u8,
+        });
+      }
     }
 
-    let index_value = self.indexes[index - 1].value();
-    value + index_value
+    Ok(())
+  }
+
+  pub fn read_memory(
+    &self,
+    instruction: &mix::Instruction,
+    address: isize,
+  ) -> Result<mix::Word, MixFault> {
+    let word = self.read_cell(instruction, address, Access::Read)?;
+    self
+      .watches
+      .report(address, Access::Read, self.program_counter, None);
+
+    Ok(word)
+  }
+
+  /// The word that a store is about to change part of. The program doesn't
+  /// see it, so it isn't reported to the watchpoints as a read.
+  pub fn read_memory_for_store(
+    &self,
+    instruction: &mix::Instruction,
+    address: isize,
+  ) -> Result<mix::Word, MixFault> {
+    self.read_cell(instruction, address, Access::Write)
+  }
+
+  fn read_cell(
+    &self,
+    instruction: &mix::Instruction,
+    address: isize,
+    access: Access,
+  ) -> Result<mix::Word, MixFault> {
+    self.check_buffers(instruction, address, access)?;
+    self
+      .memory_cell(address)
+      .ok_or_else(|| self.address_out_of_range(instruction, address))?
+      .try_read()
+      .ok_or(MixFault::MemoryLocked {
+        program_counter: self.program_counter,
+        instruction: *instruction,
+        address,
+      })
+  }
+
+  pub fn write_memory(
+    &mut self,
+    instruction: &mix::Instruction,
+    address: isize,
+    word: mix::Word,
+  ) -> Result<(), MixFault> {
+    self.check_buffers(instruction, address, Access::Write)?;
+    let cell = self
+      .memory_cell(address)
+      .ok_or_else(|| self.address_out_of_range(instruction, address))?;
+    let previous = cell.try_read();
+    cell.try_write(word).ok_or(MixFault::MemoryLocked {
+      program_counter: self.program_counter,
+      instruction: *instruction,
+      address,
+    })?;
+    self
+      .watches
+      .report(address, Access::Write, self.program_counter, None);
+    if self.is_observed() {
+      self.written.push(address);
+    }
+
+    if let Some(previous) = previous {
+      self.record_write(address, previous);
+    }
+
+    Ok(())
+  }
+
+  pub fn read_extension(&self, instruction: &mix::Instruction) -> Result<mix::Word, MixFault> {
+    self.extension.try_read().ok_or(MixFault::ExtensionLocked {
+      program_counter: self.program_counter,
+      instruction: *instruction,
+    })
+  }
+
+  pub fn write_extension(
+    &self,
+    instruction: &mix::Instruction,
+    word: mix::Word,
+  ) -> Result<(), MixFault> {
+    self
+      .extension
+      .try_write(word)
+      .ok_or(MixFault::ExtensionLocked {
+        program_counter: self.program_counter,
+        instruction: *instruction,
+      })
   }
 }
 
@@ -178,8 +458,8 @@ impl fmt::Debug for Computer {
       .iter()
       .map(|cell| cell.read())
       .enumerate()
-      .filter(|(_, val)| val.value() != 0)
-      .map(|(index, val)| format!("  {:4}: {:?}", index, val))
+      .filter(|(_, val)| val.value_in(self.byte_size) != 0)
+      .map(|(index, val)| format!("  {:4}: {}", index, val.describe(self.byte_size)))
       .collect::<Vec<String>>()
       .join("\n");
 
@@ -188,33 +468,339 @@ impl fmt::Debug for Computer {
       "\
 Computer {{
   PC:         {:?}
-  rA:         {:?}
-  rX:         {:?}
-  rI1:        {:?}
-  rI2:        {:?}
-  rI3:        {:?}
-  rI4:        {:?}
-  rI5:        {:?}
-  rI6:        {:?}
-  rJ:         {:?}
+  rA:         {}
+  rX:         {}
+  rI1:        {}
+  rI2:        {}
+  rI3:        {}
+  rI4:        {}
+  rI5:        {}
+  rI6:        {}
+  rJ:         {}
   Overflow:   {:?}
   Comparison: {:?}
+  Clock:      {}u
   Memory:
 {}
 }}",
       self.program_counter,
-      self.accumulator,
-      self.extension.read(),
-      self.indexes[0],
-      self.indexes[1],
-      self.indexes[2],
-      self.indexes[3],
-      self.indexes[4],
-      self.indexes[5],
-      self.jump_address,
+      self.accumulator.describe(self.byte_size),
+      self.extension.read().describe(self.byte_size),
+      self.indexes[0].describe(self.byte_size),
+      self.indexes[1].describe(self.byte_size),
+      self.indexes[2].describe(self.byte_size),
+      self.indexes[3].describe(self.byte_size),
+      self.indexes[4].describe(self.byte_size),
+      self.indexes[5].describe(self.byte_size),
+      self.jump_address.describe(self.byte_size),
       self.overflow,
       self.comparison,
+      self.clock,
       memory
     )
   }
 }
+
+/// Writes `instructions` into memory from location 0, for tests.
+#[cfg(test)]
+pub(crate) fn load(computer: &mut Computer, instructions: &[mix::Instruction]) {
+  for (i, instruction) in instructions.iter().enumerate() {
+    computer.memory[i].write(mix::Word::from_instruction(instruction));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // a computer can be handed to another thread, observers and all
+  #[test]
+  fn test_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<Computer>();
+  }
+
+  #[test]
+  fn test_faults() {
+    let tests = [
+      (
+        mix::Instruction {
+          address: mix::Address::zero(),
+          index_specification: 0,
+          modification: 0,
+          operation: 64,
+        },
+        MixFault::InvalidInstruction {
+          program_counter: 100,
+          instruction: mix::Instruction {
+            address: mix::Address::zero(),
+            index_specification: 0,
+            modification: 0,
+            operation: 64,
+          },
+        },
+      ),
+      (
+        mix::Instruction {
+          address: mix::Address::from_value(2000),
+          index_specification: 0,
+          modification: mix::field_spec(3, 1),
+          operation: mix::op_codes::LDA,
+        },
+        MixFault::InvalidInstruction {
+          program_counter: 100,
+          instruction: mix::Instruction {
+            address: mix::Address::from_value(2000),
+            index_specification: 0,
+            modification: mix::field_spec(3, 1),
+            operation: mix::op_codes::LDA,
+          },
+        },
+      ),
+      (
+        mix::Instruction {
+          address: mix::Address::from_value(2000),
+          index_specification: 7,
+          modification: mix::field_spec(0, 5),
+          operation: mix::op_codes::LDA,
+        },
+        MixFault::InvalidIndex {
+          program_counter: 100,
+          instruction: mix::Instruction {
+            address: mix::Address::from_value(2000),
+            index_specification: 7,
+            modification: mix::field_spec(0, 5),
+            operation: mix::op_codes::LDA,
+          },
+        },
+      ),
+      (
+        mix::Instruction {
+          address: mix::Address::from_value(3999),
+          index_specification: 1,
+          modification: mix::field_spec(0, 5),
+          operation: mix::op_codes::STA,
+        },
+        MixFault::AddressOutOfRange {
+          program_counter: 100,
+          instruction: mix::Instruction {
+            address: mix::Address::from_value(3999),
+            index_specification: 1,
+            modification: mix::field_spec(0, 5),
+            operation: mix::op_codes::STA,
+          },
+          address: 4000,
+        },
+      ),
+      (
+        mix::Instruction {
+          address: mix::Address::from_value(5),
+          index_specification: 0,
+          modification: 2,
+          operation: mix::op_codes::HLT,
+        },
+        MixFault::HaltWithCode {
+          program_counter: 100,
+          instruction: mix::Instruction {
+            address: mix::Address::from_value(5),
+            index_specification: 0,
+            modification: 2,
+            operation: mix::op_codes::HLT,
+          },
+          code: 5,
+        },
+      ),
+    ];
+
+    for (instruction, expected_fault) in &tests {
+      let mut computer = Computer::new();
+      computer.program_counter = 100;
+      computer.indexes[0] = mix::Address::from_value(1);
+      computer.memory[100].write(mix::Word::from_instruction(instruction));
+
+      assert_eq!(computer.start(), Err(expected_fault.clone()));
+      assert_eq!(computer.program_counter, 100);
+      assert_eq!(computer.running, false);
+    }
+
+    // there's nothing to fetch outside of memory, and negative locations
+    // need the interrupt facility
+    for program_counter in [4000, 5000, -1].iter() {
+      let mut computer = Computer::new();
+      computer.program_counter = *program_counter;
+      for _ in 0..2 {
+        match computer.step() {
+          Err(MixFault::AddressOutOfRange { address, .. }) => assert_eq!(address, *program_counter),
+          result => panic!("expected AddressOutOfRange, got {:?}", result),
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_device_fault() {
+    let mut computer = Computer::new();
+    let instruction = mix::Instruction {
+      address: mix::Address::from_value(1000),
+      index_specification: 0,
+      modification: 18,
+      operation: mix::op_codes::IN,
+    };
+    computer.memory[0].write(mix::Word::from_instruction(&instruction));
+
+    computer.fetch_decode_execute().unwrap();
+
+    match computer.wait_for_devices() {
+      Err(MixFault::Device {
+        program_counter, ..
+      }) => assert_eq!(program_counter, 0),
+      result => panic!("expected device fault, got {:?}", result),
+    }
+  }
+
+  #[test]
+  fn test_clock() {
+    let mut computer = Computer::new();
+    computer.indexes[0] = mix::Address::from_value(2000);
+
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::LDA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::MUL,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 3,
+        operation: mix::op_codes::MOVE,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(5),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::JMP,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::NOP,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+
+    load(&mut computer, &instructions);
+    computer.start().unwrap();
+
+    // LDA + MUL + MOVE(3) + JMP + HLT
+    assert_eq!(computer.clock, 2 + 10 + 7 + 1 + 10);
+  }
+
+  #[test]
+  fn test_decimal() {
+    let word = |value| mix::Word::from_value_in(value, 100);
+    let address = |value| mix::Address::from_value_in(value, 100);
+    let mut computer = ComputerBuilder::new()
+      .byte_size(100)
+      .accumulator(word(9_999_999_999))
+      .build()
+      .unwrap();
+
+    computer.memory[1000].write(word(2));
+    computer.memory[1001].write(word(1234));
+    computer.memory[1002].write(word(9_999_999_999));
+
+    let instructions = [
+      mix::Instruction {
+        address: address(1000),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::ADD,
+      },
+      mix::Instruction {
+        address: address(1001),
+        index_specification: 0,
+        modification: mix::field_spec(4, 4),
+        operation: mix::op_codes::LD1,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+
+    load(&mut computer, &instructions);
+    computer.start().unwrap();
+
+    assert_eq!(computer.accumulator, word(1));
+    assert_eq!(computer.overflow, true);
+    assert_eq!(computer.indexes[0], address(12));
+    assert_eq!(computer.indexes[0].bytes, [0, 12]);
+
+    // (10^10 - 1)^2 doesn't fit in an isize, but it does in rAX
+    let instructions = [
+      mix::Instruction {
+        address: address(1002),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::LDA,
+      },
+      mix::Instruction {
+        address: address(1002),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::MUL,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+      mix::Instruction {
+        address: address(1002),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::DIV,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+
+    load(&mut computer, &instructions);
+    computer.program_counter = 0;
+    computer.start().unwrap();
+
+    assert_eq!(computer.accumulator, word(9_999_999_998));
+    assert_eq!(computer.extension.read(), word(1));
+
+    computer.start().unwrap();
+
+    assert_eq!(computer.accumulator, word(9_999_999_999));
+    assert_eq!(computer.extension.read(), word(0));
+
+    assert_eq!(
+      ComputerBuilder::new().byte_size(101).build().err(),
+      Some(String::from("byte size must be between 64 and 100"))
+    );
+  }
+}
@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{limits, Computer, Limits, MemoryCell, Watches};
+
+use crate::io::{self, ActualDevice, IoDevice, IoMode};
+use crate::mix;
+use crate::mixal;
+
+const MEMORY_SIZE: usize = 4000;
+
+// tapes, disks, the card reader and punch, and the line printer
+const UNITS: usize = 21;
+
+/// Sets up a computer with something other than the defaults, which are
+/// a binary MIX with 4000 words of memory, threaded I/O, and the standard 21 units backed by
+/// files in a new temporary directory, which is removed along with the computer.
+pub struct ComputerBuilder {
+  byte_size: isize,
+  memory_size: usize,
+  io_mode: IoMode,
+  io_directory: Option<PathBuf>,
+  devices: HashMap<usize, Box<dyn ActualDevice + Send>>,
+  files: HashMap<usize, PathBuf>,
+  program: HashMap<usize, mix::Word>,
+  program_counter: isize,
+  accumulator: mix::Word,
+  extension: mix::Word,
+  // rI1 to rI6, by number
+  indexes: HashMap<usize, mix::Address>,
+  jump_address: mix::Address,
+  overflow: bool,
+  comparison: mix::Comparison,
+  limits: Limits,
+}
+
+impl Default for ComputerBuilder {
+  fn default() -> ComputerBuilder {
+    ComputerBuilder::new()
+  }
+}
+
+impl ComputerBuilder {
+  pub fn new() -> ComputerBuilder {
+    ComputerBuilder {
+      byte_size: mix::BINARY_BYTE_SIZE,
+      memory_size: MEMORY_SIZE,
+      io_mode: IoMode::Threaded,
+      io_directory: None,
+      devices: HashMap::new(),
+      files: HashMap::new(),
+      program: HashMap::new(),
+      program_counter: 0,
+      accumulator: mix::Word::zero(),
+      extension: mix::Word::zero(),
+      indexes: HashMap::new(),
+      jump_address: mix::Address::zero(),
+      overflow: false,
+      comparison: mix::Comparison::Equal,
+      limits: Limits::default(),
+    }
+  }
+
+  /// The number of values a byte can hold, from 64 for a binary MIX to 100
+  /// for a decimal one.
+  pub fn byte_size(mut self, byte_size: isize) -> ComputerBuilder {
+    self.byte_size = byte_size;
+    self
+  }
+
+  /// The number of words of memory, which has to fit in an address.
+  pub fn memory_size(mut self, words: usize) -> ComputerBuilder {
+    self.memory_size = words;
+    self
+  }
+
+  pub fn io_mode(mut self, io_mode: IoMode) -> ComputerBuilder {
+    self.io_mode = io_mode;
+    self
+  }
+
+  /// Where the files for the standard devices go, instead of the run's own
+  /// directory.
+  pub fn io_directory<P: Into<PathBuf>>(mut self, directory: P) -> ComputerBuilder {
+    self.io_directory = Some(directory.into());
+    self
+  }
+
+  /// Attaches `device` as `unit`, in place of the standard device.
+  pub fn device(mut self, unit: usize, device: Box<dyn ActualDevice + Send>) -> ComputerBuilder {
+    self.devices.insert(unit, device);
+    self
+  }
+
+  /// Uses `path` for the standard device on `unit`, instead of the usual
+  /// file in the I/O directory.
+  pub fn device_file<P: Into<PathBuf>>(mut self, unit: usize, path: P) -> ComputerBuilder {
+    self.files.insert(unit, path.into());
+    self
+  }
+
+  /// Loads `words` into memory, and starts at `start`.
+  pub fn program(mut self, words: HashMap<usize, mix::Word>, start: usize) -> ComputerBuilder {
+    self.program = words;
+    self.program_counter = start as isize;
+    self
+  }
+
+  /// Loads what `assembler` has assembled, on a machine with the same byte
+  /// size.
+  pub fn assembled(self, assembler: &mixal::Assembler) -> ComputerBuilder {
+    self
+      .byte_size(assembler.byte_size())
+      .program(assembler.words.clone(), assembler.program_start)
+  }
+
+  pub fn program_counter(mut self, address: isize) -> ComputerBuilder {
+    self.program_counter = address;
+    self
+  }
+
+  pub fn accumulator(mut self, word: mix::Word) -> ComputerBuilder {
+    self.accumulator = word;
+    self
+  }
+
+  pub fn extension(mut self, word: mix::Word) -> ComputerBuilder {
+    self.extension = word;
+    self
+  }
+
+  /// Sets rI1 to rI6, with `index` from 1.
+  pub fn index(mut self, index: usize, address: mix::Address) -> ComputerBuilder {
+    self.indexes.insert(index, address);
+    self
+  }
+
+  pub fn jump_address(mut self, address: mix::Address) -> ComputerBuilder {
+    self.jump_address = address;
+    self
+  }
+
+  pub fn overflow(mut self, overflow: bool) -> ComputerBuilder {
+    self.overflow = overflow;
+    self
+  }
+
+  pub fn comparison(mut self, comparison: mix::Comparison) -> ComputerBuilder {
+    self.comparison = comparison;
+    self
+  }
+
+  pub fn limits(mut self, limits: Limits) -> ComputerBuilder {
+    self.limits = limits;
+    self
+  }
+
+  /// Builds the computer and starts its devices.
+  pub fn build(mut self) -> Result<Computer, String> {
+    mix::check_byte_size(self.byte_size)?;
+    let addressable = (self.byte_size * self.byte_size) as usize;
+    if self.memory_size == 0 || self.memory_size > addressable {
+      return Err(format!(
+        "memory size must be between 1 and {} words",
+        addressable
+      ));
+    }
+    let units = self.devices.keys().chain(self.files.keys());
+    if let Some(unit) = units.into_iter().find(|unit| **unit >= UNITS) {
+      return Err(format!("there's no unit {}", unit));
+    }
+    if let Some(address) = self.program.keys().find(|a| **a >= self.memory_size) {
+      return Err(format!("the program doesn't fit in memory at {}", address));
+    }
+    if self.program_counter < 0 || self.program_counter >= self.memory_size as isize {
+      return Err(format!(
+        "the program can't start at {}, outside of memory",
+        self.program_counter
+      ));
+    }
+    if let Some(index) = self.indexes.keys().find(|i| !(1..=6).contains(*i)) {
+      return Err(format!("there's no rI{}", index));
+    }
+
+    let memory: Vec<MemoryCell> = (0..self.memory_size)
+      .map(|address| {
+        let word = self.program.get(&address).copied();
+        MemoryCell::new(word.unwrap_or_else(mix::Word::zero))
+      })
+      .collect();
+
+    // only make a temporary directory if a standard device needs one
+    let needs_directory = self.io_directory.is_none()
+      && (0..UNITS)
+        .any(|unit| !self.devices.contains_key(&unit) && !self.files.contains_key(&unit));
+    let temporary_directory = if needs_directory {
+      Some(TemporaryDirectory::new()?)
+    } else {
+      None
+    };
+    let directory = match &temporary_directory {
+      Some(temporary) => Some(temporary.path().to_path_buf()),
+      None => self.io_directory.clone(),
+    };
+
+    let mut io_devices = Vec::with_capacity(UNITS);
+    for unit in 0..UNITS {
+      let device = match self.devices.remove(&unit) {
+        Some(device) => IoDevice::new(device, self.io_mode),
+        None => self.standard_device(unit, directory.as_deref())?,
+      };
+      io_devices.push(device);
+    }
+
+    let mut indexes = [mix::Address::zero(); 6];
+    for (index, address) in self.indexes.iter() {
+      indexes[index - 1] = *address;
+    }
+
+    let computer = Computer {
+      byte_size: self.byte_size,
+      running: false,
+      program_counter: self.program_counter,
+      accumulator: self.accumulator,
+      extension: Arc::new(MemoryCell::new(self.extension)),
+      indexes,
+      jump_address: self.jump_address,
+      memory: Arc::new(memory),
+      overflow: self.overflow,
+      comparison: self.comparison,
+      io_devices,
+      clock: 0,
+      profile: None,
+      interrupts: None,
+      history: None,
+      watches: Arc::new(Watches::new()),
+      observers: Vec::new(),
+      limits: self.limits,
+      usage: limits::Usage::default(),
+      written: Vec::new(),
+      jumped: false,
+      temporary_directory,
+    };
+
+    for io in computer.io_devices.iter() {
+      io.start(&computer);
+    }
+
+    Ok(computer)
+  }
+
+  fn standard_device(&self, unit: usize, directory: Option<&Path>) -> Result<IoDevice, String> {
+    let path = match (self.files.get(&unit), directory) {
+      (Some(path), _) => path.clone(),
+      (None, Some(directory)) => {
+        fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+        directory.join(standard_filename(unit))
+      }
+      (None, None) => return Err(format!("there's no file for unit {}", unit)),
+    };
+    let path = path.to_str().ok_or("device paths must be UTF-8")?;
+
+    let mode = self.io_mode;
+    Ok(match unit {
+      0..=7 => io::TapeUnit::new(path, mode),
+      8..=15 => io::DiskUnit::new(path, mode),
+      16 => io::CardReader::new(path, mode),
+      17 => io::CardPunch::new(path, mode),
+      _ => io::LinePrinter::new(path, mode),
+    })
+  }
+}
+
+/// A directory for the device files of one computer, which is removed
+/// when the computer is dropped. The name has the process ID, the time and
+/// a counter in it, so that no two computers share one.
+pub struct TemporaryDirectory {
+  path: PathBuf,
+}
+
+impl TemporaryDirectory {
+  fn new() -> Result<TemporaryDirectory, String> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let started = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_nanos())
+      .unwrap_or(0);
+    loop {
+      let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+      let name = format!("negroni-io-{}-{}-{}", process::id(), started, count);
+      let path = std::env::temp_dir().join(name);
+      match fs::create_dir(&path) {
+        Ok(()) => return Ok(TemporaryDirectory { path }),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+        Err(e) => return Err(e.to_string()),
+      }
+    }
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl Drop for TemporaryDirectory {
+  fn drop(&mut self) {
+    // there's nobody to tell if this fails
+    let _ = fs::remove_dir_all(&self.path);
+  }
+}
+
+fn standard_filename(unit: usize) -> String {
+  match unit {
+    0..=7 => format!("tape{}.dat", unit),
+    8..=15 => format!("disk{}.dat", unit),
+    16 => String::from("card_reader.dat"),
+    17 => String::from("card_punch.dat"),
+    _ => String::from("line_printer.dat"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_builder() {
+    let source = String::from(
+      "\
+* adds up to 10
+         ORIG 100
+START    ENTA 0
+         ENT1 10
+LOOP     INCA 0,1
+         DEC1 1
+         J1P  LOOP
+         HLT
+         END  START
+",
+    );
+    let program = mixal::Parser::new(&source).parse().unwrap();
+    let mut assembler = mixal::Assembler::new();
+    assembler.assemble(program).unwrap();
+
+    let directory = std::env::temp_dir().join("negroni-builder-test");
+    let mut computer = ComputerBuilder::new()
+      .memory_size(200)
+      .io_mode(IoMode::Synchronous)
+      .io_directory(&directory)
+      .assembled(&assembler)
+      .index(2, mix::Address::from_value(7))
+      .build()
+      .unwrap();
+
+    assert_eq!(computer.memory.len(), 200);
+    assert_eq!(computer.program_counter, 100);
+    assert!(directory.join("tape0.dat").exists());
+
+    computer.start().unwrap();
+    assert_eq!(computer.accumulator.value(), 55);
+    assert_eq!(computer.indexes[1].value(), 7);
+
+    let errors = [
+      ComputerBuilder::new().memory_size(0).build(),
+      ComputerBuilder::new()
+        .memory_size(50)
+        .assembled(&assembler)
+        .build(),
+      ComputerBuilder::new()
+        .memory_size(100)
+        .program_counter(150)
+        .build(),
+      ComputerBuilder::new().program_counter(-1).build(),
+      ComputerBuilder::new()
+        .index(0, mix::Address::zero())
+        .build(),
+      ComputerBuilder::new()
+        .index(7, mix::Address::zero())
+        .build(),
+    ];
+    for result in errors.iter() {
+      assert!(result.is_err());
+    }
+  }
+
+  #[test]
+  fn test_temporary_directory() {
+    let computer = ComputerBuilder::new()
+      .io_mode(IoMode::Synchronous)
+      .build()
+      .unwrap();
+    let other = ComputerBuilder::new()
+      .io_mode(IoMode::Synchronous)
+      .build()
+      .unwrap();
+    let directory = computer.temporary_directory().unwrap().to_path_buf();
+
+    assert!(directory.join("tape0.dat").exists());
+    assert_ne!(other.temporary_directory(), Some(directory.as_path()));
+
+    drop(computer);
+    assert!(!directory.exists());
+    assert!(other.temporary_directory().unwrap().exists());
+
+    let given = ComputerBuilder::new()
+      .io_mode(IoMode::Synchronous)
+      .io_directory(std::env::temp_dir().join("negroni-builder-test"))
+      .build()
+      .unwrap();
+    assert_eq!(given.temporary_directory(), None);
+  }
+
+  #[test]
+  fn test_byte_size() {
+    let source = String::from(
+      "\
+* 4100 is 41 00 in decimal but 01 00 04 in binary
+         ORIG 100
+START    ENTA 4000
+         INCA 100
+         HLT
+         END  START
+",
+    );
+    let program = mixal::Parser::new(&source).parse().unwrap();
+    let mut assembler = mixal::Assembler::with_byte_size(100).unwrap();
+    assembler.assemble(program).unwrap();
+
+    // a decimal machine can have more memory, and runs alongside a binary one
+    let directory = std::env::temp_dir().join("negroni-byte-size-test");
+    let mut decimal = ComputerBuilder::new()
+      .memory_size(5000)
+      .io_mode(IoMode::Synchronous)
+      .io_directory(&directory)
+      .assembled(&assembler)
+      .build()
+      .unwrap();
+    let binary = ComputerBuilder::new()
+      .io_mode(IoMode::Synchronous)
+      .io_directory(&directory)
+      .accumulator(mix::Word::from_value(4100))
+      .build()
+      .unwrap();
+
+    assert_eq!(decimal.byte_size, 100);
+    decimal.start().unwrap();
+    assert_eq!(decimal.accumulator.value_in(100), 4100);
+    assert_eq!(decimal.accumulator.bytes, [0, 0, 0, 41, 0]);
+    assert_eq!(binary.accumulator.bytes, [0, 0, 1, 0, 4]);
+
+    let errors = [
+      ComputerBuilder::new().byte_size(63).build(),
+      ComputerBuilder::new().byte_size(101).build(),
+      ComputerBuilder::new().memory_size(5000).build(),
+    ];
+    for result in errors.iter() {
+      assert!(result.is_err());
+    }
+  }
+}
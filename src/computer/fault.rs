@@ -0,0 +1,141 @@
+use std::fmt;
+
+use super::Limit;
+
+use crate::mix;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MixFault {
+  InvalidInstruction {
+    program_counter: isize,
+    instruction: mix::Instruction,
+  },
+  InvalidIndex {
+    program_counter: isize,
+    instruction: mix::Instruction,
+  },
+  AddressOutOfRange {
+    program_counter: isize,
+    instruction: mix::Instruction,
+    address: isize,
+  },
+  MemoryLocked {
+    program_counter: isize,
+    instruction: mix::Instruction,
+    address: isize,
+  },
+  ExtensionLocked {
+    program_counter: isize,
+    instruction: mix::Instruction,
+  },
+  HaltWithCode {
+    program_counter: isize,
+    instruction: mix::Instruction,
+    code: isize,
+  },
+  Device {
+    program_counter: isize,
+    instruction: mix::Instruction,
+    message: String,
+  },
+  LimitExceeded {
+    program_counter: isize,
+    instruction: mix::Instruction,
+    limit: Limit,
+  },
+  BufferInUse {
+    program_counter: isize,
+    instruction: mix::Instruction,
+    address: isize,
+    unit: u8,
+  },
+}
+
+impl MixFault {
+  pub fn program_counter(&self) -> isize {
+    match self {
+      MixFault::InvalidInstruction {
+        program_counter, ..
+      }
+      | MixFault::InvalidIndex {
+        program_counter, ..
+      }
+      | MixFault::AddressOutOfRange {
+        program_counter, ..
+      }
+      | MixFault::MemoryLocked {
+        program_counter, ..
+      }
+      | MixFault::ExtensionLocked {
+        program_counter, ..
+      }
+      | MixFault::HaltWithCode {
+        program_counter, ..
+      }
+      | MixFault::Device {
+        program_counter, ..
+      }
+      | MixFault::LimitExceeded {
+        program_counter, ..
+      }
+      | MixFault::BufferInUse {
+        program_counter, ..
+      } => *program_counter,
+    }
+  }
+
+  pub fn instruction(&self) -> &mix::Instruction {
+    match self {
+      MixFault::InvalidInstruction { instruction, .. }
+      | MixFault::InvalidIndex { instruction, .. }
+      | MixFault::AddressOutOfRange { instruction, .. }
+      | MixFault::MemoryLocked { instruction, .. }
+      | MixFault::ExtensionLocked { instruction, .. }
+      | MixFault::HaltWithCode { instruction, .. }
+      | MixFault::Device { instruction, .. }
+      | MixFault::LimitExceeded { instruction, .. }
+      | MixFault::BufferInUse { instruction, .. } => instruction,
+    }
+  }
+
+  fn description(&self) -> String {
+    match self {
+      MixFault::InvalidInstruction { .. } => String::from("invalid instruction"),
+      MixFault::InvalidIndex { instruction, .. } => {
+        format!(
+          "index spec out of range: {}",
+          instruction.index_specification
+        )
+      }
+      MixFault::AddressOutOfRange { address, .. } => {
+        format!("address out of range: {}", address)
+      }
+      MixFault::MemoryLocked { address, .. } => {
+        format!("memory location {} is in use by an I/O device", address)
+      }
+      MixFault::ExtensionLocked { .. } => String::from("rX is in use by an I/O device"),
+      MixFault::HaltWithCode { code, .. } => format!("HLT with code {}", code),
+      MixFault::Device {
+        instruction,
+        message,
+        ..
+      } => format!("unit {}: {}", instruction.modification, message),
+      MixFault::LimitExceeded { limit, .. } => limit.to_string(),
+      MixFault::BufferInUse { address, unit, .. } => {
+        format!("memory location {} is in use by unit {}", address, unit)
+      }
+    }
+  }
+}
+
+impl fmt::Display for MixFault {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} at {:04} [{:?}]",
+      self.description(),
+      self.program_counter(),
+      mix::Word::from_instruction(self.instruction())
+    )
+  }
+}
@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+use super::Computer;
+
+use crate::mix;
+
+// the timer and saved registers, which the interrupt facility writes to
+// without going through `write_memory`
+const CONTROL_REGISTERS: std::ops::Range<isize> = -10..0;
+
+/// An undo log of the last few instructions, for stepping backwards. Each step
+/// holds the registers from before the instruction and the previous contents
+/// of any memory it wrote to.
+///
+/// Transfers made by I/O devices aren't recorded, so stepping back over an IN
+/// leaves the input in memory, and the device doesn't rewind.
+pub struct History {
+  limit: usize,
+  steps: VecDeque<Step>,
+}
+
+struct Step {
+  program_counter: isize,
+  accumulator: mix::Word,
+  extension: mix::Word,
+  indexes: [mix::Address; 6],
+  jump_address: mix::Address,
+  overflow: bool,
+  comparison: mix::Comparison,
+  clock: usize,
+  control: Option<Control>,
+  memory: Vec<(isize, mix::Word)>,
+}
+
+struct Control {
+  control_state: bool,
+  registers: Vec<mix::Word>,
+  pending: VecDeque<isize>,
+}
+
+impl History {
+  fn new(limit: usize) -> History {
+    History {
+      limit,
+      steps: VecDeque::new(),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.steps.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.steps.is_empty()
+  }
+}
+
+impl Computer {
+  /// Starts keeping an undo log of up to `limit` instructions.
+  pub fn enable_history(&mut self, limit: usize) {
+    self.history = Some(History::new(limit));
+  }
+
+  /// Called before each instruction to save the registers.
+  pub(super) fn record_step(&mut self) {
+    match &self.history {
+      Some(history) if history.limit > 0 => {}
+      _ => return,
+    }
+
+    let control = self.interrupts.as_ref().map(|interrupts| Control {
+      control_state: interrupts.control_state,
+      registers: CONTROL_REGISTERS
+        .map(|address| interrupts.cell(address).read())
+        .collect(),
+      pending: interrupts.pending.clone(),
+    });
+
+    let step = Step {
+      program_counter: self.program_counter,
+      accumulator: self.accumulator,
+      extension: self.extension.read(),
+      indexes: self.indexes,
+      jump_address: self.jump_address,
+      overflow: self.overflow,
+      comparison: self.comparison,
+      clock: self.clock,
+      control,
+      memory: Vec::new(),
+    };
+
+    let history = self.history.as_mut().unwrap();
+    if history.steps.len() == history.limit {
+      history.steps.pop_front();
+    }
+    history.steps.push_back(step);
+  }
+
+  /// Called by `write_memory` with what was there before.
+  pub(super) fn record_write(&mut self, address: isize, previous: mix::Word) {
+    let step = self
+      .history
+      .as_mut()
+      .and_then(|history| history.steps.back_mut());
+    if let Some(step) = step {
+      step.memory.push((address, previous));
+    }
+  }
+
+  /// Undoes the last instruction. Returns false if there's nothing left in
+  /// the history.
+  pub fn step_back(&mut self) -> bool {
+    let step = match self.history.as_mut().and_then(|h| h.steps.pop_back()) {
+      Some(step) => step,
+      None => return false,
+    };
+
+    for (address, word) in step.memory.into_iter().rev() {
+      if let Some(cell) = self.memory_cell(address) {
+        cell.write(word);
+      }
+    }
+
+    self.program_counter = step.program_counter;
+    self.accumulator = step.accumulator;
+    self.extension.write(step.extension);
+    self.indexes = step.indexes;
+    self.jump_address = step.jump_address;
+    self.overflow = step.overflow;
+    self.comparison = step.comparison;
+    self.clock = step.clock;
+
+    if let (Some(control), Some(interrupts)) = (step.control, self.interrupts.as_mut()) {
+      interrupts.control_state = control.control_state;
+      for (address, word) in CONTROL_REGISTERS.zip(control.registers) {
+        interrupts.cell(address).write(word);
+      }
+      interrupts.pending = control.pending;
+    }
+
+    true
+  }
+
+  /// Steps back until `stop` returns true, or the history runs out. Returns
+  /// whether it stopped because of `stop`.
+  pub fn reverse_continue<F>(&mut self, mut stop: F) -> bool
+  where
+    F: FnMut(&Self) -> bool,
+  {
+    while self.step_back() {
+      if stop(self) {
+        return true;
+      }
+    }
+
+    false
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::computer::load;
+
+  #[test]
+  fn test_history() {
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(5),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::ENTA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(2000),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::STA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::INCA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(2000),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::STA,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+
+    let run = |computer: &mut Computer| {
+      load(computer, &instructions);
+      computer.start().unwrap();
+    };
+
+    let mut computer = Computer::new();
+    computer.enable_history(3);
+    run(&mut computer);
+    assert_eq!(computer.history.as_ref().unwrap().len(), 3);
+
+    assert!(computer.step_back());
+    assert_eq!(computer.program_counter, 4);
+    assert_eq!(computer.memory[2000].read(), mix::Word::from_value(6));
+
+    assert!(computer.step_back());
+    assert_eq!(computer.program_counter, 3);
+    assert_eq!(computer.accumulator, mix::Word::from_value(6));
+    assert_eq!(computer.memory[2000].read(), mix::Word::from_value(5));
+
+    assert!(computer.step_back());
+    assert_eq!(computer.program_counter, 2);
+    assert_eq!(computer.accumulator, mix::Word::from_value(5));
+    assert_eq!(computer.clock, 3);
+
+    // the limit has been reached
+    assert!(!computer.step_back());
+
+    let mut computer = Computer::new();
+    computer.enable_history(100);
+    run(&mut computer);
+
+    assert!(computer.reverse_continue(|computer| computer.program_counter == 1));
+    assert_eq!(computer.accumulator, mix::Word::from_value(5));
+    assert_eq!(computer.memory[2000].read(), mix::Word::zero());
+    assert!(!computer.reverse_continue(|_| false));
+    assert_eq!(computer.program_counter, 0);
+  }
+}
@@ -0,0 +1,393 @@
+use std::collections::VecDeque;
+
+use super::{Computer, MemoryCell};
+
+use crate::mix;
+
+// the optional interrupt facility from exercise 1.4.4-18
+const CONTROL_MEMORY_SIZE: usize = 3999;
+
+const TIMER: isize = -10;
+const TIMER_INTERRUPT: isize = -11;
+pub const INT_INTERRUPT: isize = -12;
+
+fn device_interrupt(unit: usize) -> isize {
+  -20 - unit as isize
+}
+
+// registers are saved in -9 to -1 when an interrupt happens: rA, rI1 to rI6,
+// rX, and then a word holding the location of the next instruction in (0:2),
+// 8 * overflow + comparison in (3:3), and rJ in (4:5)
+const SAVED_REGISTERS: isize = -9;
+
+pub struct Interrupts {
+  pub control_state: bool,
+  pub memory: Vec<MemoryCell>,
+  pub pending: VecDeque<isize>,
+}
+
+impl Interrupts {
+  fn new() -> Interrupts {
+    let memory = (0..CONTROL_MEMORY_SIZE)
+      .map(|_| MemoryCell::new(mix::Word::zero()))
+      .collect();
+
+    Interrupts {
+      control_state: true,
+      memory,
+      pending: VecDeque::new(),
+    }
+  }
+
+  /// The cell for a negative location, from -1 down to -3999.
+  pub fn cell(&self, address: isize) -> &MemoryCell {
+    &self.memory[(-address - 1) as usize]
+  }
+}
+
+impl Computer {
+  /// Adds the interrupt facility to this computer. The machine starts out in
+  /// control state, so that it can set up the negative locations before
+  /// returning to normal state with INT.
+  pub fn enable_interrupts(&mut self) {
+    for device in self.io_devices.iter() {
+      // forget about anything that finished before now
+      device.take_interrupt();
+    }
+
+    self.interrupts = Some(Interrupts::new());
+  }
+
+  pub fn in_control_state(&self) -> bool {
+    match &self.interrupts {
+      Some(interrupts) => interrupts.control_state,
+      None => false,
+    }
+  }
+
+  /// Saves the registers and enters control state at `location`, with
+  /// execution resuming at `next` after the matching INT.
+  pub fn interrupt(&mut self, location: isize, next: isize) {
+    let comparison = match self.comparison {
+      mix::Comparison::Less => 0,
+      mix::Comparison::Equal => 1,
+      mix::Comparison::Greater => 2,
+    };
+    let next = mix::Address::from_value_in(next, self.byte_size);
+
+    let mut registers = vec![self.accumulator];
+    registers.extend(self.indexes.iter().map(|index| index.cast_to_word()));
+    registers.push(self.extension.read());
+    registers.push(mix::Word {
+      sign: next.sign,
+      bytes: [
+        next.bytes[0],
+        next.bytes[1],
+        8 * self.overflow as u8 + comparison,
+        self.jump_address.bytes[0],
+        self.jump_address.bytes[1],
+      ],
+    });
+
+    let interrupts = self.interrupts.as_mut().unwrap();
+    for (i, word) in registers.into_iter().enumerate() {
+      interrupts.cell(SAVED_REGISTERS + i as isize).write(word);
+    }
+    interrupts.control_state = true;
+    self.program_counter = location;
+  }
+
+  /// Restores the registers saved by `interrupt` and returns to normal state.
+  pub fn return_from_interrupt(&mut self) {
+    let interrupts = self.interrupts.as_mut().unwrap();
+    let registers: Vec<mix::Word> = (0..9)
+      .map(|i| interrupts.cell(SAVED_REGISTERS + i).read())
+      .collect();
+    interrupts.control_state = false;
+
+    self.accumulator = registers[0];
+    for i in 0..6 {
+      self.indexes[i] = registers[1 + i].cast_to_address();
+    }
+    self.extension.write(registers[7]);
+
+    let state = registers[8];
+    self.overflow = state.bytes[2] / 8 != 0;
+    self.comparison = match state.bytes[2] % 8 {
+      0 => mix::Comparison::Less,
+      2 => mix::Comparison::Greater,
+      _ => mix::Comparison::Equal,
+    };
+    self.jump_address = mix::Address {
+      bytes: [state.bytes[3], state.bytes[4]],
+      sign: mix::Sign::Positive,
+    };
+    self.program_counter = mix::Address {
+      bytes: [state.bytes[0], state.bytes[1]],
+      sign: state.sign,
+    }
+    .value_in(self.byte_size);
+  }
+
+  /// Queues up an interrupt for each device that has finished since we last
+  /// looked, including the time that its operation takes.
+  pub(super) fn collect_device_interrupts(&mut self) {
+    let interrupts = match self.interrupts.as_mut() {
+      Some(interrupts) => interrupts,
+      None => return,
+    };
+
+    for (unit, device) in self.io_devices.iter().enumerate() {
+      if device.ready_at() <= self.clock && device.take_interrupt() {
+        interrupts.pending.push_back(device_interrupt(unit));
+      }
+    }
+  }
+
+  /// Called after each instruction: runs the timer, picks up any finished
+  /// I/O, and interrupts if there's anything pending and we're in normal state.
+  pub(super) fn check_interrupts(&mut self, time: usize) {
+    let interrupts = match self.interrupts.as_mut() {
+      Some(interrupts) => interrupts,
+      None => return,
+    };
+
+    let timer = interrupts.cell(TIMER);
+    let before = timer.read().value_in(self.byte_size);
+    if before > 0 {
+      let after = (before - time as isize).max(0);
+      timer.write(mix::Word::from_value_in(after, self.byte_size));
+      if after == 0 {
+        interrupts.pending.push_back(TIMER_INTERRUPT);
+      }
+    }
+
+    self.collect_device_interrupts();
+
+    let interrupts = self.interrupts.as_mut().unwrap();
+    if interrupts.control_state {
+      return;
+    }
+
+    if let Some(location) = interrupts.pending.pop_front() {
+      let next = self.program_counter;
+      self.interrupt(location, next);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::computer::MixFault;
+
+  // like `load`, but with a location for each instruction, which can be in
+  // the negative memory of the interrupt facility
+  fn load_at(computer: &mut Computer, instructions: &[(isize, mix::Instruction)]) {
+    for (address, instruction) in instructions.iter() {
+      let word = mix::Word::from_instruction(instruction);
+      if *address < 0 {
+        let interrupts = computer.interrupts.as_ref().unwrap();
+        interrupts.cell(*address).write(word);
+      } else {
+        computer.memory[*address as usize].write(word);
+      }
+    }
+  }
+
+  #[test]
+  fn test_interrupts() {
+    let mut computer = Computer::new();
+    computer.enable_interrupts();
+    computer.interrupts.as_mut().unwrap().control_state = false;
+
+    computer.program_counter = 100;
+    computer.accumulator = mix::Word::from_value(7);
+    computer.indexes[0] = mix::Address::from_value(3);
+    computer.jump_address = mix::Address::from_value(55);
+    computer.overflow = true;
+    computer.comparison = mix::Comparison::Greater;
+
+    let instructions = [
+      (
+        100,
+        mix::Instruction {
+          address: mix::Address::zero(),
+          index_specification: 0,
+          modification: 9,
+          operation: mix::op_codes::INT,
+        },
+      ),
+      (
+        101,
+        mix::Instruction {
+          address: mix::Address::zero(),
+          index_specification: 0,
+          modification: 2,
+          operation: mix::op_codes::HLT,
+        },
+      ),
+      (
+        -12,
+        mix::Instruction {
+          address: mix::Address::from_value(500),
+          index_specification: 0,
+          modification: 0,
+          operation: mix::op_codes::JMP,
+        },
+      ),
+      (
+        500,
+        mix::Instruction {
+          address: mix::Address::from_value(-1),
+          index_specification: 0,
+          modification: mix::field_spec(0, 5),
+          operation: mix::op_codes::LDA,
+        },
+      ),
+      (
+        501,
+        mix::Instruction {
+          address: mix::Address::from_value(1000),
+          index_specification: 0,
+          modification: mix::field_spec(0, 5),
+          operation: mix::op_codes::STA,
+        },
+      ),
+      (
+        502,
+        mix::Instruction {
+          address: mix::Address::from_value(9),
+          index_specification: 0,
+          modification: 2,
+          operation: mix::op_codes::ENT1,
+        },
+      ),
+      (
+        503,
+        mix::Instruction {
+          address: mix::Address::zero(),
+          index_specification: 0,
+          modification: 9,
+          operation: mix::op_codes::INT,
+        },
+      ),
+    ];
+
+    load_at(&mut computer, &instructions);
+    computer.start().unwrap();
+
+    // the saved state holds the next location, overflow and comparison, and rJ
+    assert_eq!(
+      computer.memory[1000].read(),
+      mix::Word {
+        bytes: [1, 37, 10, 0, 55],
+        sign: mix::Sign::Positive,
+      }
+    );
+    assert_eq!(computer.program_counter, 102);
+    assert_eq!(computer.accumulator, mix::Word::from_value(7));
+    assert_eq!(computer.indexes[0], mix::Address::from_value(3));
+    assert_eq!(computer.jump_address, mix::Address::from_value(55));
+    assert_eq!(computer.overflow, true);
+    assert_eq!(computer.comparison, mix::Comparison::Greater);
+    assert_eq!(computer.in_control_state(), false);
+
+    // negative locations are out of reach in normal state
+    let instruction = mix::Instruction {
+      address: mix::Address::from_value(-1),
+      index_specification: 0,
+      modification: mix::field_spec(0, 5),
+      operation: mix::op_codes::LDA,
+    };
+    assert_eq!(
+      instruction.decode().unwrap().execute(&mut computer),
+      Err(MixFault::AddressOutOfRange {
+        program_counter: 102,
+        instruction,
+        address: -1,
+      })
+    );
+  }
+
+  #[test]
+  fn test_device_interrupt() {
+    let mut computer = Computer::new();
+    computer.enable_interrupts();
+    computer.interrupts.as_mut().unwrap().control_state = false;
+
+    let instructions = [
+      (
+        0,
+        mix::Instruction {
+          address: mix::Address::from_value(1000),
+          index_specification: 0,
+          modification: 5,
+          operation: mix::op_codes::OUT,
+        },
+      ),
+      (
+        1,
+        mix::Instruction {
+          address: mix::Address::from_value(1),
+          index_specification: 0,
+          modification: 5,
+          operation: mix::op_codes::JBUS,
+        },
+      ),
+      (
+        2,
+        mix::Instruction {
+          address: mix::Address::zero(),
+          index_specification: 0,
+          modification: 2,
+          operation: mix::op_codes::HLT,
+        },
+      ),
+      (
+        -25,
+        mix::Instruction {
+          address: mix::Address::from_value(600),
+          index_specification: 0,
+          modification: 0,
+          operation: mix::op_codes::JMP,
+        },
+      ),
+      (
+        600,
+        mix::Instruction {
+          address: mix::Address::from_value(42),
+          index_specification: 0,
+          modification: 2,
+          operation: mix::op_codes::ENTA,
+        },
+      ),
+      (
+        601,
+        mix::Instruction {
+          address: mix::Address::from_value(3000),
+          index_specification: 0,
+          modification: mix::field_spec(0, 5),
+          operation: mix::op_codes::STA,
+        },
+      ),
+      (
+        602,
+        mix::Instruction {
+          address: mix::Address::zero(),
+          index_specification: 0,
+          modification: 9,
+          operation: mix::op_codes::INT,
+        },
+      ),
+    ];
+
+    load_at(&mut computer, &instructions);
+    computer.start().unwrap();
+
+    assert_eq!(computer.memory[3000].read(), mix::Word::from_value(42));
+    assert_eq!(computer.accumulator, mix::Word::zero());
+    assert_eq!(computer.program_counter, 3);
+  }
+}
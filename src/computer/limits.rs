@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use super::{Computer, MixFault};
+
+use crate::mix;
+
+/// Bounds on how much a program may do, for running programs that might
+/// never halt. A limit stops the machine before the instruction that would
+/// go over it, so the registers and memory are as they were after the last
+/// instruction that ran.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Limits {
+  /// The most instructions to run.
+  pub instructions: Option<usize>,
+  /// No instruction starts once the clock has reached this many units.
+  pub time: Option<usize>,
+  /// Real time, counted from the start of each run. Waiting for a device
+  /// counts too, except in synchronous mode, where a device can't be
+  /// interrupted once it has started.
+  pub wall_clock: Option<Duration>,
+  /// The most blocks that OUT can write to each unit, such as lines on the
+  /// line printer.
+  pub output: Option<usize>,
+}
+
+/// Which limit stopped the machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Limit {
+  Instructions(usize),
+  Time(usize),
+  WallClock(Duration),
+  Output { unit: u8, blocks: usize },
+}
+
+impl Limit {
+  /// A different code for each kind of limit, for use as an exit status.
+  /// Other faults are 1.
+  pub fn code(&self) -> i32 {
+    match self {
+      Limit::Instructions(_) => 2,
+      Limit::Time(_) => 3,
+      Limit::WallClock(_) => 4,
+      Limit::Output { .. } => 5,
+    }
+  }
+}
+
+impl fmt::Display for Limit {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Limit::Instructions(count) => write!(f, "instruction limit of {} reached", count),
+      Limit::Time(units) => write!(f, "time limit of {}u reached", units),
+      Limit::WallClock(duration) => write!(f, "wall clock limit of {:?} reached", duration),
+      Limit::Output { unit, blocks } => {
+        write!(
+          f,
+          "output limit of {} blocks reached on unit {}",
+          blocks, unit
+        )
+      }
+    }
+  }
+}
+
+/// What has counted towards the limits so far.
+#[derive(Default)]
+pub(super) struct Usage {
+  instructions: usize,
+  started: Option<Instant>,
+  output: HashMap<u8, usize>,
+}
+
+impl Computer {
+  /// Called before each instruction, to stop if it would go over a limit.
+  pub(super) fn check_limits(&mut self, instruction: &mix::Instruction) -> Result<(), MixFault> {
+    let limit = self.exceeded_limit(instruction);
+    match limit {
+      Some(limit) => Err(MixFault::LimitExceeded {
+        program_counter: self.program_counter,
+        instruction: *instruction,
+        limit,
+      }),
+      None => Ok(()),
+    }
+  }
+
+  fn exceeded_limit(&mut self, instruction: &mix::Instruction) -> Option<Limit> {
+    let limits = &self.limits;
+    let usage = &mut self.usage;
+
+    if let Some(count) = limits.instructions {
+      if usage.instructions >= count {
+        return Some(Limit::Instructions(count));
+      }
+    }
+    if let Some(units) = limits.time {
+      if self.clock >= units {
+        return Some(Limit::Time(units));
+      }
+    }
+    if let Some(duration) = limits.wall_clock {
+      let started = *usage.started.get_or_insert_with(Instant::now);
+      if started.elapsed() >= duration {
+        return Some(Limit::WallClock(duration));
+      }
+    }
+    if let Some(blocks) = limits.output {
+      let unit = instruction.modification;
+      let written = usage.output.get(&unit).copied().unwrap_or(0);
+      if instruction.operation == mix::op_codes::OUT && written >= blocks {
+        return Some(Limit::Output { unit, blocks });
+      }
+    }
+
+    None
+  }
+
+  /// Starts the wall clock again, as a run starts.
+  pub(crate) fn start_wall_clock(&mut self) {
+    self.usage.started = Some(Instant::now());
+  }
+
+  /// When the wall clock limit runs out, if there is one and it has started.
+  pub(crate) fn deadline(&self) -> Option<Instant> {
+    Some(self.usage.started? + self.limits.wall_clock?)
+  }
+
+  /// The fault for running out of time while `instruction` waits for a
+  /// device.
+  pub(crate) fn wall_clock_exceeded(&self, instruction: &mix::Instruction) -> MixFault {
+    MixFault::LimitExceeded {
+      program_counter: self.program_counter,
+      instruction: *instruction,
+      limit: Limit::WallClock(self.limits.wall_clock.unwrap_or_default()),
+    }
+  }
+
+  /// Called after each instruction that ran.
+  pub(super) fn record_usage(&mut self, instruction: &mix::Instruction) {
+    self.usage.instructions += 1;
+    if instruction.operation == mix::op_codes::OUT {
+      *self
+        .usage
+        .output
+        .entry(instruction.modification)
+        .or_insert(0) += 1;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::sync::mpsc;
+
+  use crate::computer::ComputerBuilder;
+  use crate::io::{ActualDevice, SlimComputer};
+
+  fn load(computer: &mut Computer, instructions: &[(isize, u8, u8)]) {
+    for (i, (address, field, operation)) in instructions.iter().enumerate() {
+      let instruction = mix::Instruction {
+        address: mix::Address::from_value(*address),
+        index_specification: 0,
+        modification: *field,
+        operation: *operation,
+      };
+      computer.memory[i].write(mix::Word::from_instruction(&instruction));
+    }
+  }
+
+  #[test]
+  fn test_instruction_limit() {
+    let mut computer = Computer::new();
+    // an endless loop of INCA 1
+    load(
+      &mut computer,
+      &[(1, 0, mix::op_codes::INCA), (0, 0, mix::op_codes::JMP)],
+    );
+    computer.limits.instructions = Some(9);
+
+    match computer.start() {
+      Err(MixFault::LimitExceeded {
+        program_counter,
+        limit,
+        ..
+      }) => {
+        assert_eq!(program_counter, 1);
+        assert_eq!(limit, Limit::Instructions(9));
+        assert_eq!(limit.code(), 2);
+      }
+      result => panic!("expected the instruction limit, got {:?}", result),
+    }
+    assert_eq!(computer.accumulator.value(), 5);
+    assert_eq!(computer.clock, 9);
+    assert!(!computer.running);
+
+    // raising the limit carries on from the same place
+    computer.limits.instructions = Some(10);
+    assert!(computer.start().is_err());
+    assert_eq!(computer.program_counter, 0);
+    assert_eq!(computer.accumulator.value(), 5);
+  }
+
+  #[test]
+  fn test_time_limit() {
+    let mut computer = Computer::new();
+    load(&mut computer, &[(0, 0, mix::op_codes::JMP)]);
+    computer.limits.time = Some(100);
+
+    let fault = computer.start().unwrap_err();
+    assert!(fault.to_string().starts_with("time limit of 100u"));
+    assert_eq!(computer.clock, 100);
+  }
+
+  // a device that never finishes reading
+  struct Stuck(mpsc::Receiver<()>);
+
+  impl ActualDevice for Stuck {
+    fn read(&mut self, _computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+      self.0.recv().map_err(|e| e.to_string())?;
+      Ok(vec![mix::Word::zero()])
+    }
+
+    fn write(&mut self, _words: &[mix::Word], _computer: &SlimComputer) -> Result<(), String> {
+      Ok(())
+    }
+
+    fn control(&mut self, _m: isize, _computer: &SlimComputer) -> Result<(), String> {
+      Ok(())
+    }
+
+    fn block_size(&self) -> usize {
+      1
+    }
+  }
+
+  #[test]
+  fn test_wall_clock_limit() {
+    let (_sender, receiver) = mpsc::channel();
+    let mut computer = ComputerBuilder::new()
+      .device(20, Box::new(Stuck(receiver)))
+      .limits(Limits {
+        wall_clock: Some(Duration::from_millis(50)),
+        ..Limits::default()
+      })
+      .build()
+      .unwrap();
+    load(
+      &mut computer,
+      &[
+        (1000, 20, mix::op_codes::IN),
+        (1000, 20, mix::op_codes::IN),
+        (0, 2, mix::op_codes::HLT),
+      ],
+    );
+
+    // the second IN waits for the first one, which never finishes
+    match computer.start() {
+      Err(MixFault::LimitExceeded {
+        program_counter,
+        limit,
+        ..
+      }) => {
+        assert_eq!(program_counter, 1);
+        assert_eq!(limit.code(), 4);
+      }
+      result => panic!("expected the wall clock limit, got {:?}", result),
+    }
+    assert!(computer.wait_for_devices().is_err());
+
+    // each run gets the whole of the limit again
+    computer.limits.wall_clock = Some(Duration::from_millis(500));
+    let restarted = Instant::now();
+    assert!(computer.start().is_err());
+    assert!(restarted.elapsed() >= Duration::from_millis(500));
+  }
+
+  #[test]
+  fn test_output_limit() {
+    let mut computer = Computer::new();
+    load(
+      &mut computer,
+      &[
+        (1000, 18, mix::op_codes::OUT),
+        (1000, 18, mix::op_codes::OUT),
+        (0, 2, mix::op_codes::HLT),
+      ],
+    );
+    computer.limits.output = Some(1);
+
+    let fault = computer.start().unwrap_err();
+    computer.wait_for_devices().unwrap();
+    assert_eq!(
+      fault,
+      MixFault::LimitExceeded {
+        program_counter: 1,
+        instruction: *fault.instruction(),
+        limit: Limit::Output {
+          unit: 18,
+          blocks: 1
+        },
+      }
+    );
+  }
+}
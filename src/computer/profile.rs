@@ -0,0 +1,126 @@
+pub struct Profile {
+  pub executions: Vec<usize>,
+  pub times: Vec<usize>,
+  pub jumps: Vec<usize>,
+}
+
+impl Profile {
+  pub fn new(size: usize) -> Profile {
+    Profile {
+      executions: vec![0; size],
+      times: vec![0; size],
+      jumps: vec![0; size],
+    }
+  }
+
+  pub fn record(&mut self, location: usize, time: usize, jumped: bool) {
+    self.executions[location] += 1;
+    self.times[location] += time;
+    if jumped {
+      self.jumps[location] += 1;
+    }
+  }
+
+  /// Joins the counts with a listing from `shake --listing`, where each line
+  /// starts with the location it was assembled to (if any). Without a listing,
+  /// every location that ran gets a line of its own.
+  pub fn report(&self, listing: Option<&str>) -> String {
+    let mut lines = vec![format!(
+      "{:>10} {:>10} {:>10}  {}",
+      "COUNT", "TIME", "JUMPS", "SOURCE"
+    )];
+
+    match listing {
+      Some(listing) => {
+        for line in listing.lines() {
+          let location = line.get(..4).and_then(|l| l.parse::<usize>().ok());
+          lines.push(match location {
+            Some(location) if location < self.executions.len() => {
+              format!("{}  {}", self.counts(location), line)
+            }
+            _ => format!("{:32}  {}", "", line),
+          });
+        }
+      }
+      None => {
+        for location in 0..self.executions.len() {
+          if self.executions[location] > 0 {
+            lines.push(format!("{}  {:04}", self.counts(location), location));
+          }
+        }
+      }
+    }
+
+    lines.join("\n")
+  }
+
+  fn counts(&self, location: usize) -> String {
+    format!(
+      "{:>10} {:>10} {:>10}",
+      self.executions[location], self.times[location], self.jumps[location]
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::computer::{load, Computer};
+  use crate::mix;
+
+  #[test]
+  fn test_profile() {
+    let mut computer = Computer::new();
+    computer.enable_profiling();
+
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(5),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::ENT1,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1),
+        index_specification: 0,
+        modification: 1,
+        operation: mix::op_codes::DEC1,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::J1P,
+      },
+      // a jump to the next location is still a jump
+      mix::Instruction {
+        address: mix::Address::from_value(4),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::JMP,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+
+    load(&mut computer, &instructions);
+    computer.start().unwrap();
+
+    let profile = computer.profile.as_ref().unwrap();
+    assert_eq!(profile.executions[..6], [1, 5, 5, 1, 1, 0]);
+    assert_eq!(profile.times[..6], [1, 5, 5, 1, 10, 0]);
+    assert_eq!(profile.jumps[..6], [0, 0, 4, 1, 0, 0]);
+
+    let listing = "0001  LOOP DEC1 1\n      * comment";
+    assert_eq!(
+      profile.report(Some(listing)),
+      format!(
+        "     COUNT       TIME      JUMPS  SOURCE\n         5          5          0  0001  LOOP DEC1 1\n{:32}        * comment",
+        ""
+      )
+    );
+  }
+}
@@ -0,0 +1,199 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+use bincode;
+use serde::{Deserialize, Serialize};
+
+use super::{Computer, ComputerBuilder};
+
+use crate::mix;
+
+/// Everything needed to carry on running a computer later: the registers,
+/// memory, clock and how far along each device is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+  pub program_counter: isize,
+  pub accumulator: mix::Word,
+  pub extension: mix::Word,
+  pub indexes: [mix::Address; 6],
+  pub jump_address: mix::Address,
+  pub memory: Vec<mix::Word>,
+  pub overflow: bool,
+  pub comparison: mix::Comparison,
+  pub clock: usize,
+  pub byte_size: isize,
+  pub device_positions: Vec<u64>,
+  pub interrupts: Option<ControlState>,
+}
+
+/// The state of the interrupt facility, if it's enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlState {
+  pub control_state: bool,
+  pub memory: Vec<mix::Word>,
+  pub pending: Vec<isize>,
+}
+
+impl Snapshot {
+  pub fn load(path: &str) -> Result<Snapshot, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    bincode::deserialize_from(BufReader::new(file)).map_err(|e| e.to_string())
+  }
+
+  pub fn save(&self, path: &str) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    bincode::serialize_into(BufWriter::new(file), self).map_err(|e| e.to_string())
+  }
+}
+
+impl Computer {
+  /// Takes a snapshot once the devices have finished what they're doing, so
+  /// that nothing is locked. Any device faults are left for the next
+  /// instruction that uses the device.
+  pub fn snapshot(&mut self) -> Snapshot {
+    for device in self.io_devices.iter() {
+      device.wait_ready();
+    }
+    self.collect_device_interrupts();
+
+    let interrupts = self.interrupts.as_ref().map(|interrupts| ControlState {
+      control_state: interrupts.control_state,
+      memory: interrupts.memory.iter().map(|cell| cell.read()).collect(),
+      pending: interrupts.pending.iter().cloned().collect(),
+    });
+
+    Snapshot {
+      program_counter: self.program_counter,
+      accumulator: self.accumulator,
+      extension: self.extension.read(),
+      indexes: self.indexes,
+      jump_address: self.jump_address,
+      memory: self.memory.iter().map(|cell| cell.read()).collect(),
+      overflow: self.overflow,
+      comparison: self.comparison,
+      clock: self.clock,
+      byte_size: self.byte_size,
+      device_positions: self.io_devices.iter().map(|d| d.position()).collect(),
+      interrupts,
+    }
+  }
+
+  /// Builds a new computer in the state captured by `snapshot`.
+  pub fn from_snapshot(snapshot: &Snapshot) -> Result<Computer, String> {
+    let mut computer = ComputerBuilder::new()
+      .byte_size(snapshot.byte_size)
+      .build()?;
+    computer.restore(snapshot)?;
+    Ok(computer)
+  }
+
+  /// Puts this computer in the state captured by `snapshot`. The byte size
+  /// has to match.
+  pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), String> {
+    if snapshot.byte_size != self.byte_size {
+      return Err(format!(
+        "snapshot has a byte size of {}, expected {}",
+        snapshot.byte_size, self.byte_size
+      ));
+    }
+
+    if snapshot.memory.len() != self.memory.len() {
+      return Err(format!(
+        "snapshot has {} words of memory, expected {}",
+        snapshot.memory.len(),
+        self.memory.len()
+      ));
+    }
+    if snapshot.device_positions.len() != self.io_devices.len() {
+      return Err(format!(
+        "snapshot has {} devices, expected {}",
+        snapshot.device_positions.len(),
+        self.io_devices.len()
+      ));
+    }
+
+    self.program_counter = snapshot.program_counter;
+    self.accumulator = snapshot.accumulator;
+    self.extension.write(snapshot.extension);
+    self.indexes = snapshot.indexes;
+    self.jump_address = snapshot.jump_address;
+    for (cell, word) in self.memory.iter().zip(snapshot.memory.iter()) {
+      cell.write(*word);
+    }
+    self.overflow = snapshot.overflow;
+    self.comparison = snapshot.comparison;
+    self.clock = snapshot.clock;
+
+    for (device, position) in self.io_devices.iter().zip(&snapshot.device_positions) {
+      device.set_position(*position);
+    }
+
+    if let Some(state) = &snapshot.interrupts {
+      self.enable_interrupts();
+      let interrupts = self.interrupts.as_mut().unwrap();
+      if state.memory.len() != interrupts.memory.len() {
+        return Err(format!(
+          "snapshot has {} words of control memory, expected {}",
+          state.memory.len(),
+          interrupts.memory.len()
+        ));
+      }
+
+      interrupts.control_state = state.control_state;
+      for (cell, word) in interrupts.memory.iter().zip(state.memory.iter()) {
+        cell.write(*word);
+      }
+      interrupts.pending = state.pending.iter().cloned().collect();
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_snapshot() {
+    let mut computer = Computer::new();
+    computer.enable_interrupts();
+    computer.program_counter = 1234;
+    computer.accumulator = mix::Word::from_value(-42);
+    computer.extension.write(mix::Word::from_value(7));
+    computer.indexes[2] = mix::Address::from_value(-300);
+    computer.jump_address = mix::Address::from_value(99);
+    computer.memory[3999].write(mix::Word::from_value(12345));
+    computer.overflow = true;
+    computer.comparison = mix::Comparison::Greater;
+    computer.clock = 1000;
+    computer.io_devices[3].set_position(400);
+    let interrupts = computer.interrupts.as_ref().unwrap();
+    interrupts.cell(-5).write(mix::Word::from_value(5));
+
+    let snapshot = computer.snapshot();
+    let path = std::env::temp_dir().join("negroni_test_snapshot.bin");
+    let path = path.to_str().unwrap();
+    snapshot.save(path).unwrap();
+    let loaded = Snapshot::load(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(loaded, snapshot);
+
+    let restored = Computer::from_snapshot(&loaded).unwrap();
+    assert_eq!(restored.program_counter, 1234);
+    assert_eq!(restored.accumulator, mix::Word::from_value(-42));
+    assert_eq!(restored.extension.read(), mix::Word::from_value(7));
+    assert_eq!(restored.indexes[2], mix::Address::from_value(-300));
+    assert_eq!(restored.jump_address, mix::Address::from_value(99));
+    assert_eq!(restored.memory[3999].read(), mix::Word::from_value(12345));
+    assert!(restored.overflow);
+    assert_eq!(restored.comparison, mix::Comparison::Greater);
+    assert_eq!(restored.clock, 1000);
+    assert_eq!(restored.io_devices[3].position(), 400);
+    assert!(restored.in_control_state());
+    assert_eq!(
+      restored.interrupts.as_ref().unwrap().cell(-5).read(),
+      mix::Word::from_value(5)
+    );
+  }
+}
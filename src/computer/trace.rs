@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use serde_json::{json, Value};
+
+use super::Computer;
+
+use crate::mix;
+use crate::mix::disasm::{self, Labels};
+
+/// What an instruction did, as seen once it has finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+  // the machine's byte size, for the values of the words
+  pub byte_size: isize,
+  pub location: isize,
+  pub instruction: mix::Word,
+  // the address part plus the index register, if the index is valid
+  pub effective_address: Option<isize>,
+  pub accumulator: mix::Word,
+  pub extension: mix::Word,
+  pub indexes: [mix::Address; 6],
+  pub jump_address: mix::Address,
+  pub overflow: bool,
+  pub comparison: mix::Comparison,
+  // memory written by the instruction itself, but not by I/O devices
+  pub changes: Vec<(isize, mix::Word)>,
+}
+
+/// Gets told about every instruction that runs without a fault. Observers
+/// have to be `Send`, so that a computer can be moved to another thread.
+pub trait Observer: Send {
+  fn executed(&mut self, entry: &TraceEntry);
+
+  /// Called once the run is over, to report anything that went wrong while
+  /// observing it.
+  fn finish(&mut self) -> Result<(), String> {
+    Ok(())
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TraceFormat {
+  Text,
+  JsonLines,
+}
+
+impl TraceFormat {
+  pub fn parse(name: &str) -> Option<TraceFormat> {
+    match name {
+      "text" => Some(TraceFormat::Text),
+      "json" | "jsonl" => Some(TraceFormat::JsonLines),
+      _ => None,
+    }
+  }
+}
+
+/// Writes a line for each instruction to `output`.
+pub struct Trace<W: Write> {
+  output: W,
+  format: TraceFormat,
+  // symbols from the assembler, for naming addresses
+  labels: Option<HashMap<String, isize>>,
+  // the first write that failed; nothing more is written after it
+  error: Option<io::Error>,
+}
+
+impl<W: Write> Trace<W> {
+  pub fn new(output: W, format: TraceFormat) -> Trace<W> {
+    Trace {
+      output,
+      format,
+      labels: None,
+      error: None,
+    }
+  }
+
+  pub fn with_labels(mut self, symbols: HashMap<String, isize>) -> Trace<W> {
+    self.labels = Some(symbols);
+    self
+  }
+}
+
+impl<W: Write + Send> Observer for Trace<W> {
+  fn executed(&mut self, entry: &TraceEntry) {
+    if self.error.is_some() {
+      return;
+    }
+
+    let labels = self.labels.as_ref().map(|l| l as &dyn Labels);
+    let line = match self.format {
+      TraceFormat::Text => text_line(entry, labels),
+      TraceFormat::JsonLines => json_line(entry, labels),
+    };
+    if let Err(error) = writeln!(self.output, "{}", line) {
+      self.error = Some(error);
+    }
+  }
+
+  fn finish(&mut self) -> Result<(), String> {
+    let result = match self.error.take() {
+      Some(error) => Err(error),
+      None => self.output.flush(),
+    };
+    result.map_err(|error| format!("couldn't write the trace: {}", error))
+  }
+}
+
+impl Computer {
+  pub fn add_observer(&mut self, observer: Box<dyn Observer>) {
+    self.observers.push(observer);
+  }
+
+  /// Removes the observers, telling each one that the run is over. Returns
+  /// the first error that any of them reports.
+  pub fn finish_observers(&mut self) -> Result<(), String> {
+    let mut result = Ok(());
+    for mut observer in self.observers.drain(..) {
+      let finished = observer.finish();
+      result = result.and(finished);
+    }
+    result
+  }
+
+  pub(super) fn is_observed(&self) -> bool {
+    !self.observers.is_empty()
+  }
+
+  /// Called after each instruction has run, with the memory it wrote to.
+  pub(super) fn notify_observers(
+    &mut self,
+    location: isize,
+    instruction: &mix::Instruction,
+    effective_address: Option<isize>,
+  ) {
+    let mut written = std::mem::take(&mut self.written);
+    written.dedup();
+    let changes = written
+      .into_iter()
+      .filter_map(|address| {
+        let word = self.memory_cell(address)?.try_read()?;
+        Some((address, word))
+      })
+      .collect();
+
+    let entry = TraceEntry {
+      byte_size: self.byte_size,
+      location,
+      instruction: mix::Word::from_instruction(instruction),
+      effective_address,
+      accumulator: self.accumulator,
+      extension: self.extension.read(),
+      indexes: self.indexes,
+      jump_address: self.jump_address,
+      overflow: self.overflow,
+      comparison: self.comparison,
+      changes,
+    };
+
+    for observer in self.observers.iter_mut() {
+      observer.executed(&entry);
+    }
+  }
+}
+
+fn signed(sign: mix::Sign, value: isize, width: usize) -> String {
+  let sign = match sign {
+    mix::Sign::Positive => '+',
+    mix::Sign::Negative => '-',
+  };
+  format!("{}{:0width$}", sign, value.abs(), width = width)
+}
+
+fn comparison_text(comparison: mix::Comparison) -> &'static str {
+  match comparison {
+    mix::Comparison::Less => "L",
+    mix::Comparison::Equal => "E",
+    mix::Comparison::Greater => "G",
+  }
+}
+
+/// One line per instruction, with the registers after it ran and then any
+/// memory that it changed.
+pub fn text_line(entry: &TraceEntry, labels: Option<&dyn Labels>) -> String {
+  let byte_size = entry.byte_size;
+  let word = |word: &mix::Word| signed(word.sign, word.value_in(byte_size), 10);
+  let address = |address: &mix::Address| signed(address.sign, address.value_in(byte_size), 4);
+
+  let mut line = format!(
+    "{:04}  {:<16}  M={}  rA={} rX={}",
+    entry.location,
+    disasm::to_mixal_with_labels(entry.instruction, Some(entry.location), labels, byte_size),
+    match entry.effective_address {
+      Some(m) => format!("{:<5}", m),
+      None => String::from("?    "),
+    },
+    word(&entry.accumulator),
+    word(&entry.extension)
+  );
+  for (i, index) in entry.indexes.iter().enumerate() {
+    line.push_str(&format!(" rI{}={}", i + 1, address(index)));
+  }
+  line.push_str(&format!(
+    " rJ={} OV={} CI={}",
+    address(&entry.jump_address),
+    entry.overflow as u8,
+    comparison_text(entry.comparison)
+  ));
+  for (location, value) in entry.changes.iter() {
+    line.push_str(&format!("  [{:04}]={}", location, word(value)));
+  }
+
+  line
+}
+
+fn sign_text(sign: mix::Sign) -> &'static str {
+  match sign {
+    mix::Sign::Positive => "+",
+    mix::Sign::Negative => "-",
+  }
+}
+
+/// The same as `text_line`, as a JSON object. Words and addresses have their
+/// sign apart from their value, so that a negative zero keeps it.
+pub fn json_line(entry: &TraceEntry, labels: Option<&dyn Labels>) -> String {
+  let byte_size = entry.byte_size;
+  let word = |word: &mix::Word| json!({"sign": sign_text(word.sign), "value": word.value_in(byte_size).abs()});
+  let address = |address: &mix::Address| json!({"sign": sign_text(address.sign), "value": address.value_in(byte_size).abs()});
+
+  let line = json!({
+    "pc": entry.location,
+    "instruction": disasm::to_mixal_with_labels(
+      entry.instruction,
+      Some(entry.location),
+      labels,
+      byte_size
+    ),
+    "address": entry.effective_address,
+    "rA": word(&entry.accumulator),
+    "rX": word(&entry.extension),
+    "rI": entry.indexes.iter().map(address).collect::<Vec<Value>>(),
+    "rJ": address(&entry.jump_address),
+    "overflow": entry.overflow,
+    "comparison": comparison_text(entry.comparison),
+    "changes": entry
+      .changes
+      .iter()
+      .map(|(location, value)| json!({"address": location, "value": word(value)}))
+      .collect::<Vec<Value>>(),
+  });
+  line.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::sync::{Arc, Mutex};
+
+  use crate::computer::load;
+
+  struct Recorder {
+    entries: Arc<Mutex<Vec<TraceEntry>>>,
+  }
+
+  impl Observer for Recorder {
+    fn executed(&mut self, entry: &TraceEntry) {
+      self.entries.lock().unwrap().push(entry.clone());
+    }
+  }
+
+  #[test]
+  fn test_trace() {
+    let mut computer = Computer::new();
+    let entries = Arc::new(Mutex::new(Vec::new()));
+    computer.add_observer(Box::new(Recorder {
+      entries: entries.clone(),
+    }));
+    computer.indexes[0] = mix::Address::from_value(1);
+
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(-5),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::ENTA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(2000),
+        index_specification: 1,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::STA,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+
+    load(&mut computer, &instructions);
+    computer.start().unwrap();
+
+    let entries = entries.lock().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[1].effective_address, Some(2001));
+    assert_eq!(entries[1].changes, vec![(2001, mix::Word::from_value(-5))]);
+
+    assert_eq!(
+      text_line(&entries[1], None),
+      "0001  STA 2000,1        M=2001   rA=-0000000005 rX=+0000000000 \
+       rI1=+0001 rI2=+0000 rI3=+0000 rI4=+0000 rI5=+0000 rI6=+0000 \
+       rJ=+0000 OV=0 CI=E  [2001]=-0000000005"
+    );
+    let zero = json!({"sign": "+", "value": 0});
+    assert_eq!(
+      serde_json::from_str::<Value>(&json_line(&entries[1], None)).unwrap(),
+      json!({
+        "pc": 1,
+        "instruction": "STA 2000,1",
+        "address": 2001,
+        "rA": {"sign": "-", "value": 5},
+        "rX": zero,
+        "rI": [{"sign": "+", "value": 1}, zero, zero, zero, zero, zero],
+        "rJ": zero,
+        "overflow": false,
+        "comparison": "E",
+        "changes": [{"address": 2001, "value": {"sign": "-", "value": 5}}],
+      })
+    );
+
+    let mut symbols = HashMap::new();
+    symbols.insert(String::from("BUF"), 1998);
+    assert!(text_line(&entries[1], Some(&symbols)).starts_with("0001  STA BUF+2,1  "));
+  }
+
+  #[test]
+  fn test_json_line() {
+    let mut entry = TraceEntry {
+      byte_size: mix::BINARY_BYTE_SIZE,
+      location: 0,
+      instruction: mix::Word::from_instruction(&mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::LDA,
+      }),
+      effective_address: None,
+      accumulator: mix::Word::zero(),
+      extension: mix::Word::zero(),
+      indexes: [mix::Address::zero(); 6],
+      jump_address: mix::Address::zero(),
+      overflow: false,
+      comparison: mix::Comparison::Equal,
+      changes: vec![],
+    };
+    entry.accumulator.sign = mix::Sign::Negative;
+
+    let mut symbols = HashMap::new();
+    symbols.insert(String::from("SAY\"HI\\"), 1000);
+    let line: Value = serde_json::from_str(&json_line(&entry, Some(&symbols))).unwrap();
+    assert_eq!(line["instruction"], "LDA SAY\"HI\\");
+    assert_eq!(line["address"], Value::Null);
+    assert_eq!(line["rA"], json!({"sign": "-", "value": 0}));
+  }
+
+  struct Broken;
+
+  impl Write for Broken {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+      Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_trace_error() {
+    let mut computer = Computer::new();
+    computer.add_observer(Box::new(Trace::new(Broken, TraceFormat::Text)));
+    computer.memory[0].write(mix::Word::from_instruction(&mix::Instruction {
+      address: mix::Address::zero(),
+      index_specification: 0,
+      modification: 2,
+      operation: mix::op_codes::HLT,
+    }));
+
+    assert!(computer.start().is_ok());
+    assert_eq!(
+      computer.finish_observers(),
+      Err(String::from("couldn't write the trace: broken pipe"))
+    );
+    assert!(computer.observers.is_empty());
+  }
+}
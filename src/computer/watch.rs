@@ -0,0 +1,197 @@
+use std::sync::{Mutex, RwLock};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Access {
+  Read,
+  Write,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WatchKind {
+  Read,
+  Write,
+  Any,
+}
+
+/// Stops the machine when any of `length` locations from `start` are
+/// accessed in the way given by `kind`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Watchpoint {
+  pub start: isize,
+  pub length: usize,
+  pub kind: WatchKind,
+}
+
+impl Watchpoint {
+  fn matches(&self, address: isize, access: Access) -> bool {
+    let kind = matches!(
+      (self.kind, access),
+      (WatchKind::Any, _) | (WatchKind::Read, Access::Read) | (WatchKind::Write, Access::Write)
+    );
+
+    kind && address >= self.start && address < self.start + self.length as isize
+  }
+}
+
+/// An access to a watched location. `unit` is set when the access was made by
+/// an I/O device, in which case `program_counter` is where the IN or OUT was.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WatchHit {
+  pub address: isize,
+  pub access: Access,
+  pub program_counter: isize,
+  pub unit: Option<u8>,
+}
+
+/// The watchpoints for a computer, shared with the device threads so that
+/// they can report their transfers.
+#[derive(Default)]
+pub struct Watches {
+  watchpoints: RwLock<Vec<Watchpoint>>,
+  hits: Mutex<Vec<WatchHit>>,
+}
+
+impl Watches {
+  pub fn new() -> Watches {
+    Watches {
+      watchpoints: RwLock::new(Vec::new()),
+      hits: Mutex::new(Vec::new()),
+    }
+  }
+
+  pub fn add(&self, watchpoint: Watchpoint) {
+    self.watchpoints.write().unwrap().push(watchpoint);
+  }
+
+  /// Removes the watchpoints starting at `start`, returning whether there
+  /// were any.
+  pub fn remove(&self, start: isize) -> bool {
+    let mut watchpoints = self.watchpoints.write().unwrap();
+    let before = watchpoints.len();
+    watchpoints.retain(|watchpoint| watchpoint.start != start);
+    watchpoints.len() != before
+  }
+
+  pub fn list(&self) -> Vec<Watchpoint> {
+    self.watchpoints.read().unwrap().clone()
+  }
+
+  pub fn report(&self, address: isize, access: Access, program_counter: isize, unit: Option<u8>) {
+    let watchpoints = self.watchpoints.read().unwrap();
+    if watchpoints.iter().any(|w| w.matches(address, access)) {
+      self.hits.lock().unwrap().push(WatchHit {
+        address,
+        access,
+        program_counter,
+        unit,
+      });
+    }
+  }
+
+  /// Takes the accesses to watched locations since the last call. Devices
+  /// work in the background, so their accesses may turn up a few
+  /// instructions after the IN or OUT.
+  pub fn take_hits(&self) -> Vec<WatchHit> {
+    let mut hits = self.hits.lock().unwrap();
+    hits.drain(..).collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::computer::{load, Computer};
+  use crate::mix;
+
+  #[test]
+  fn test_watchpoints() {
+    let mut computer = Computer::new();
+    computer.watches.add(Watchpoint {
+      start: 2000,
+      length: 2,
+      kind: WatchKind::Any,
+    });
+    computer.watches.add(Watchpoint {
+      start: 3050,
+      length: 1,
+      kind: WatchKind::Write,
+    });
+
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 7,
+        operation: mix::op_codes::OUT,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(-1),
+        index_specification: 0,
+        modification: 7,
+        operation: mix::op_codes::IOC,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(3000),
+        index_specification: 0,
+        modification: 7,
+        operation: mix::op_codes::IN,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(2000),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::STA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(2001),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::LDA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(2002),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::LDA,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+
+    load(&mut computer, &instructions);
+    computer.start().unwrap();
+    computer.wait_for_devices().unwrap();
+
+    let mut hits = computer.watches.take_hits();
+    hits.sort_by_key(|hit| hit.address);
+    // storing doesn't count as reading the old word
+    assert_eq!(
+      hits,
+      vec![
+        WatchHit {
+          address: 2000,
+          access: Access::Write,
+          program_counter: 3,
+          unit: None,
+        },
+        WatchHit {
+          address: 2001,
+          access: Access::Read,
+          program_counter: 4,
+          unit: None,
+        },
+        WatchHit {
+          address: 3050,
+          access: Access::Write,
+          program_counter: 2,
+          unit: Some(7),
+        },
+      ]
+    );
+  }
+}
@@ -0,0 +1,751 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::computer::{Access, Computer, MemoryCell, MixFault, WatchHit, WatchKind, Watchpoint};
+use crate::mix;
+use crate::mix::disasm::{self, Labels};
+
+mod condition;
+pub mod dap;
+pub mod gdb;
+
+pub use condition::Condition;
+
+const HELP: &str = "\
+step [N]          run N instructions (default 1)
+continue          run until a breakpoint, HLT or a fault
+back [N]          undo N instructions (default 1)
+reverse           undo instructions until a breakpoint
+break ADDR        stop before running the instruction at ADDR
+break ADDR if C   only stop if C holds, e.g. rI1 = 17 or CONTENTS(BUF+3)(1:2) > 5
+delete ADDR       remove the breakpoint at ADDR
+watch ADDR [N]    stop when any of N locations from ADDR are written
+rwatch ADDR [N]   stop when any of N locations from ADDR are read
+awatch ADDR [N]   stop when any of N locations from ADDR are read or written
+unwatch ADDR      remove the watchpoints starting at ADDR
+print REG         show a register: rA, rX, rI1-rI6, rJ, PC, OV or CI
+print ADDR [N]    show N memory locations starting at ADDR (default 1)
+set REG VALUE     change a register
+set ADDR VALUE    change a memory location
+info devices      show the state of each I/O unit
+info breakpoints  list the breakpoints
+info watchpoints  list the watchpoints
+quit              stop debugging
+
+ADDR can be a number, or an expression using the program's symbols, such as BUF+3";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Register {
+  A,
+  X,
+  I(usize),
+  J,
+  ProgramCounter,
+  Overflow,
+  Comparison,
+}
+
+impl Register {
+  pub fn parse(name: &str) -> Option<Register> {
+    let name = name.to_lowercase();
+    match name.as_str() {
+      "ra" | "a" => Some(Register::A),
+      "rx" | "x" => Some(Register::X),
+      "rj" | "j" => Some(Register::J),
+      "pc" => Some(Register::ProgramCounter),
+      "ov" => Some(Register::Overflow),
+      "ci" => Some(Register::Comparison),
+      _ => {
+        let index = name.strip_prefix("ri").or_else(|| name.strip_prefix('i'))?;
+        match index.parse() {
+          Ok(i) if (1..=6).contains(&i) => Some(Register::I(i)),
+          _ => None,
+        }
+      }
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Target {
+  Register(Register),
+  Memory(isize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+  Step(usize),
+  Continue,
+  Back(usize),
+  Reverse,
+  Break(isize, Option<String>),
+  Delete(isize),
+  Watch(Watchpoint),
+  Unwatch(isize),
+  Print(Target, usize),
+  Set(Target, isize),
+  InfoDevices,
+  InfoBreakpoints,
+  InfoWatchpoints,
+  Help,
+  Quit,
+}
+
+impl Command {
+  pub fn parse(line: &str) -> Result<Command, String> {
+    Command::parse_with_symbols(line, &HashMap::new())
+  }
+
+  /// Parses a command where addresses can be written with symbols from the
+  /// program, such as `break LOOP` or `print BUF+3 10`.
+  pub fn parse_with_symbols(
+    line: &str,
+    symbols: &HashMap<String, isize>,
+  ) -> Result<Command, String> {
+    let address = |text: &str| parse_address(text, symbols);
+
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (name, args) = match words.split_first() {
+      Some((name, args)) => (*name, args),
+      None => return Err(String::from("no command given")),
+    };
+
+    let command = match (name, args) {
+      ("step", []) | ("s", []) => Command::Step(1),
+      ("step", [n]) | ("s", [n]) => Command::Step(parse_count(n)?),
+      ("continue", []) | ("c", []) => Command::Continue,
+      ("back", []) => Command::Back(1),
+      ("back", [n]) => Command::Back(parse_count(n)?),
+      ("reverse", []) | ("rc", []) => Command::Reverse,
+      ("break", [text]) | ("b", [text]) => Command::Break(address(text)?, None),
+      ("break", [text, "if", condition @ ..]) | ("b", [text, "if", condition @ ..]) => {
+        Command::Break(address(text)?, Some(condition.join(" ")))
+      }
+      ("delete", [text]) | ("d", [text]) => Command::Delete(address(text)?),
+      ("watch", args) => parse_watchpoint(args, WatchKind::Write, symbols)?,
+      ("rwatch", args) => parse_watchpoint(args, WatchKind::Read, symbols)?,
+      ("awatch", args) => parse_watchpoint(args, WatchKind::Any, symbols)?,
+      ("unwatch", [text]) => Command::Unwatch(address(text)?),
+      ("print", [target]) | ("p", [target]) => Command::Print(parse_target(target, symbols)?, 1),
+      ("print", [target, n]) | ("p", [target, n]) => {
+        Command::Print(parse_target(target, symbols)?, parse_count(n)?)
+      }
+      ("set", [target, value]) => {
+        Command::Set(parse_target(target, symbols)?, parse_number(value)?)
+      }
+      ("info", ["devices"]) => Command::InfoDevices,
+      ("info", ["breakpoints"]) => Command::InfoBreakpoints,
+      ("info", ["watchpoints"]) => Command::InfoWatchpoints,
+      ("help", []) | ("h", []) => Command::Help,
+      ("quit", []) | ("q", []) => Command::Quit,
+      _ => return Err(format!("unknown command: {} (try help)", line.trim())),
+    };
+
+    Ok(command)
+  }
+}
+
+fn parse_number(text: &str) -> Result<isize, String> {
+  text
+    .parse()
+    .map_err(|_| format!("expected a number, got {}", text))
+}
+
+// a number, or an expression using the program's symbols
+fn parse_address(text: &str, symbols: &HashMap<String, isize>) -> Result<isize, String> {
+  match text.parse() {
+    Ok(address) => Ok(address),
+    Err(_) => condition::address(text, symbols),
+  }
+}
+
+fn parse_count(text: &str) -> Result<usize, String> {
+  text
+    .parse()
+    .map_err(|_| format!("expected a count, got {}", text))
+}
+
+fn parse_watchpoint(
+  args: &[&str],
+  kind: WatchKind,
+  symbols: &HashMap<String, isize>,
+) -> Result<Command, String> {
+  let (start, length) = match args {
+    [start] => (parse_address(start, symbols)?, 1),
+    [start, length] => (parse_address(start, symbols)?, parse_count(length)?),
+    _ => return Err(String::from("expected an address and an optional count")),
+  };
+
+  Ok(Command::Watch(Watchpoint {
+    start,
+    length,
+    kind,
+  }))
+}
+
+fn parse_target(text: &str, symbols: &HashMap<String, isize>) -> Result<Target, String> {
+  match Register::parse(text) {
+    Some(register) => Ok(Target::Register(register)),
+    None => parse_address(text, symbols).map(Target::Memory),
+  }
+}
+
+/// Why the machine stopped running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stop {
+  Steps,
+  Breakpoint(isize),
+  BadCondition(isize, String),
+  Watchpoint(Vec<WatchHit>),
+  Halted,
+  Fault(MixFault),
+}
+
+#[derive(Default)]
+pub struct Debugger {
+  pub breakpoints: BTreeMap<isize, Option<Condition>>,
+  // for the names in commands and breakpoint conditions, and for labelling
+  // addresses
+  pub symbols: HashMap<String, isize>,
+  // the source line for each location, from the assembler
+  pub lines: HashMap<usize, usize>,
+  // the fault that the last run ended with, if it ended with one
+  pub fault: Option<MixFault>,
+}
+
+impl Debugger {
+  pub fn new() -> Debugger {
+    Debugger {
+      breakpoints: BTreeMap::new(),
+      symbols: HashMap::new(),
+      lines: HashMap::new(),
+      fault: None,
+    }
+  }
+
+  /// Parses a command, using the symbols for addresses.
+  pub fn parse(&self, line: &str) -> Result<Command, String> {
+    Command::parse_with_symbols(line, &self.symbols)
+  }
+
+  /// Whether there's a breakpoint at the program counter, and its condition
+  /// (if it has one) holds.
+  fn at_breakpoint(&self, computer: &Computer) -> Result<bool, String> {
+    match self.breakpoints.get(&computer.program_counter) {
+      None => Ok(false),
+      Some(None) => Ok(true),
+      Some(Some(condition)) => condition.evaluate(computer, &self.symbols),
+    }
+  }
+
+  /// Runs `steps` instructions, or until something stops the machine if
+  /// there's no limit. Breakpoints are only checked after the first
+  /// instruction, so that we can carry on from one. Watchpoints stop the
+  /// machine after the instruction that hit them.
+  pub fn run(&self, computer: &mut Computer, steps: Option<usize>) -> Stop {
+    computer.running = true;
+    computer.start_wall_clock();
+
+    let mut count = 0;
+    loop {
+      if let Err(fault) = computer.step() {
+        return Stop::Fault(fault);
+      }
+      count += 1;
+
+      let hits = computer.watches.take_hits();
+      if !hits.is_empty() {
+        return Stop::Watchpoint(hits);
+      }
+      if !computer.running {
+        return Stop::Halted;
+      }
+      match self.at_breakpoint(computer) {
+        Ok(true) => return Stop::Breakpoint(computer.program_counter),
+        Ok(false) => {}
+        Err(message) => return Stop::BadCondition(computer.program_counter, message),
+      }
+      if steps == Some(count) {
+        return Stop::Steps;
+      }
+    }
+  }
+
+  fn record_fault(&mut self, stop: &Stop) {
+    self.fault = match stop {
+      Stop::Fault(fault) => Some(fault.clone()),
+      _ => None,
+    };
+  }
+
+  /// Carries out a command, returning what to show the user.
+  pub fn execute(&mut self, computer: &mut Computer, command: &Command) -> Result<String, String> {
+    match *command {
+      Command::Step(n) if n > 0 => {
+        let stop = self.run(computer, Some(n));
+        self.record_fault(&stop);
+        Ok(self.describe_stop(computer, stop))
+      }
+      Command::Step(_) => Ok(self.location(computer)),
+      Command::Continue => {
+        let stop = self.run(computer, None);
+        self.record_fault(&stop);
+        Ok(self.describe_stop(computer, stop))
+      }
+      Command::Back(n) => {
+        for _ in 0..n {
+          if !computer.step_back() {
+            return Err(format!("no more history\n{}", self.location(computer)));
+          }
+        }
+        Ok(self.location(computer))
+      }
+      Command::Reverse => {
+        // a condition that can't be evaluated stops us, as it does going forwards
+        if computer.reverse_continue(|c| self.at_breakpoint(c).unwrap_or(true)) {
+          Ok(format!("breakpoint\n{}", self.location(computer)))
+        } else {
+          Err(format!("no more history\n{}", self.location(computer)))
+        }
+      }
+      Command::Break(address, None) => {
+        self.breakpoints.insert(address, None);
+        Ok(format!("breakpoint at {}", self.address_text(address)))
+      }
+      Command::Break(address, Some(ref condition)) => {
+        let condition = Condition::parse(condition)?;
+        let description = format!(
+          "breakpoint at {} if {}",
+          self.address_text(address),
+          condition
+        );
+        self.breakpoints.insert(address, Some(condition));
+        Ok(description)
+      }
+      Command::Delete(address) => {
+        if self.breakpoints.remove(&address).is_some() {
+          Ok(format!("deleted breakpoint at {:04}", address))
+        } else {
+          Err(format!("no breakpoint at {:04}", address))
+        }
+      }
+      Command::Watch(watchpoint) => {
+        cell(computer, watchpoint.start)?;
+        computer.watches.add(watchpoint);
+        Ok(describe_watchpoint(&watchpoint))
+      }
+      Command::Unwatch(address) => {
+        if computer.watches.remove(address) {
+          Ok(format!("deleted watchpoints at {:04}", address))
+        } else {
+          Err(format!("no watchpoints at {:04}", address))
+        }
+      }
+      Command::Print(Target::Register(register), _) => Ok(read_register(computer, register)),
+      Command::Print(Target::Memory(address), n) => {
+        let mut lines = Vec::new();
+        for address in address..address + n as isize {
+          let word = cell(computer, address)?.try_read();
+          lines.push(match word {
+            Some(word) => self.describe_word(address, word, computer.byte_size),
+            None => format!("{:04}: in use by an I/O device", address),
+          });
+        }
+        Ok(lines.join("\n"))
+      }
+      Command::Set(Target::Register(register), value) => {
+        write_register(computer, register, value)?;
+        Ok(read_register(computer, register))
+      }
+      Command::Set(Target::Memory(address), value) => {
+        let word = to_word(value, computer.byte_size)?;
+        cell(computer, address)?
+          .try_write(word)
+          .ok_or_else(|| format!("{:04} is in use by an I/O device", address))?;
+        Ok(self.describe_word(address, word, computer.byte_size))
+      }
+      Command::InfoDevices => {
+        let lines: Vec<String> = computer
+          .io_devices
+          .iter()
+          .enumerate()
+          .map(|(unit, device)| {
+            format!(
+              "unit {:2}: {:5}  block size {:3}  position {}",
+              unit,
+              if device.busy(computer.clock) {
+                "busy"
+              } else {
+                "ready"
+              },
+              device.block_size,
+              device.position()
+            )
+          })
+          .collect();
+        Ok(lines.join("\n"))
+      }
+      Command::InfoBreakpoints => {
+        if self.breakpoints.is_empty() {
+          return Ok(String::from("no breakpoints"));
+        }
+        let lines: Vec<String> = self
+          .breakpoints
+          .iter()
+          .map(|(address, condition)| match condition {
+            Some(condition) => format!("{} if {}", self.address_text(*address), condition),
+            None => self.address_text(*address),
+          })
+          .collect();
+        Ok(lines.join("\n"))
+      }
+      Command::InfoWatchpoints => {
+        let watchpoints = computer.watches.list();
+        if watchpoints.is_empty() {
+          return Ok(String::from("no watchpoints"));
+        }
+        let lines: Vec<String> = watchpoints.iter().map(describe_watchpoint).collect();
+        Ok(lines.join("\n"))
+      }
+      Command::Help => Ok(String::from(HELP)),
+      Command::Quit => Ok(String::new()),
+    }
+  }
+
+  fn describe_stop(&self, computer: &Computer, stop: Stop) -> String {
+    let reason = match stop {
+      Stop::Steps => return self.location(computer),
+      Stop::Breakpoint(_) => String::from("breakpoint"),
+      Stop::BadCondition(_, message) => format!("breakpoint condition failed: {}", message),
+      Stop::Watchpoint(hits) => {
+        let lines: Vec<String> = hits.iter().map(describe_hit).collect();
+        lines.join("\n")
+      }
+      Stop::Halted => String::from("halted"),
+      Stop::Fault(fault) => format!("FAULT: {}", fault),
+    };
+
+    format!("{}\n{}", reason, self.location(computer))
+  }
+
+  /// The next instruction to run.
+  fn location(&self, computer: &Computer) -> String {
+    match cell(computer, computer.program_counter) {
+      Ok(cell) => match cell.try_read() {
+        Some(word) => {
+          let text = self.describe_word(computer.program_counter, word, computer.byte_size);
+          match self.lines.get(&(computer.program_counter as usize)) {
+            Some(line) => format!("{}  (line {})", text, line),
+            None => text,
+          }
+        }
+        None => format!("{:04}", computer.program_counter),
+      },
+      Err(_) => format!("{:04}: outside of memory", computer.program_counter),
+    }
+  }
+
+  fn describe_word(&self, address: isize, word: mix::Word, byte_size: isize) -> String {
+    let labels: &dyn Labels = &self.symbols;
+    format!(
+      "{}: {}  {}",
+      self.address_text(address),
+      word.describe(byte_size),
+      disasm::to_mixal_with_labels(word, Some(address), Some(labels), byte_size)
+    )
+  }
+
+  /// An address, and the nearest symbol if there are any.
+  fn address_text(&self, address: isize) -> String {
+    match self.symbols.label(address) {
+      Some(label) => format!("{:04} <{}>", address, label),
+      None => format!("{:04}", address),
+    }
+  }
+}
+
+fn describe_watchpoint(watchpoint: &Watchpoint) -> String {
+  let kind = match watchpoint.kind {
+    WatchKind::Read => "read",
+    WatchKind::Write => "write",
+    WatchKind::Any => "access",
+  };
+  let end = watchpoint.start + watchpoint.length as isize - 1;
+
+  format!("watch {} {:04}-{:04}", kind, watchpoint.start, end)
+}
+
+fn describe_hit(hit: &WatchHit) -> String {
+  let access = match hit.access {
+    Access::Read => "read",
+    Access::Write => "written",
+  };
+
+  match hit.unit {
+    Some(unit) => format!(
+      "watchpoint: {:04} {} by unit {} (from {:04})",
+      hit.address, access, unit, hit.program_counter
+    ),
+    None => format!(
+      "watchpoint: {:04} {} at {:04}",
+      hit.address, access, hit.program_counter
+    ),
+  }
+}
+
+// the debugger can see control state memory whenever the interrupt facility
+// is enabled, not just in control state
+fn cell(computer: &Computer, address: isize) -> Result<&MemoryCell, String> {
+  computer
+    .memory_cell(address)
+    .ok_or_else(|| format!("address out of range: {}", address))
+}
+
+fn read_register(computer: &Computer, register: Register) -> String {
+  let byte_size = computer.byte_size;
+  match register {
+    Register::A => format!("rA: {}", computer.accumulator.describe(byte_size)),
+    Register::X => format!("rX: {}", computer.extension.read().describe(byte_size)),
+    Register::I(i) => format!("rI{}: {}", i, computer.indexes[i - 1].describe(byte_size)),
+    Register::J => format!("rJ: {}", computer.jump_address.describe(byte_size)),
+    Register::ProgramCounter => format!("PC: {:04}", computer.program_counter),
+    Register::Overflow => format!("OV: {}", computer.overflow),
+    Register::Comparison => format!("CI: {:?}", computer.comparison),
+  }
+}
+
+// the comparison indicator is -1, 0 or 1, the same as `write_register` takes
+fn register_value(computer: &Computer, register: Register) -> isize {
+  let byte_size = computer.byte_size;
+  match register {
+    Register::A => computer.accumulator.value_in(byte_size),
+    Register::X => computer.extension.read().value_in(byte_size),
+    Register::I(i) => computer.indexes[i - 1].value_in(byte_size),
+    Register::J => computer.jump_address.value_in(byte_size),
+    Register::ProgramCounter => computer.program_counter,
+    Register::Overflow => computer.overflow as isize,
+    Register::Comparison => match computer.comparison {
+      mix::Comparison::Less => -1,
+      mix::Comparison::Equal => 0,
+      mix::Comparison::Greater => 1,
+    },
+  }
+}
+
+fn write_register(computer: &mut Computer, register: Register, value: isize) -> Result<(), String> {
+  let byte_size = computer.byte_size;
+  match register {
+    Register::A => computer.accumulator = to_word(value, byte_size)?,
+    Register::X => computer
+      .extension
+      .try_write(to_word(value, byte_size)?)
+      .ok_or("rX is in use by an I/O device")?,
+    Register::I(i) => computer.indexes[i - 1] = to_address(value, byte_size)?,
+    Register::J => computer.jump_address = to_address(value, byte_size)?,
+    Register::ProgramCounter => {
+      if !computer.is_valid_address(value) {
+        return Err(format!("{} is outside of memory", value));
+      }
+      computer.program_counter = value
+    }
+    Register::Overflow => computer.overflow = value != 0,
+    Register::Comparison => {
+      computer.comparison = match value.signum() {
+        -1 => mix::Comparison::Less,
+        0 => mix::Comparison::Equal,
+        _ => mix::Comparison::Greater,
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn to_word(value: isize, byte_size: isize) -> Result<mix::Word, String> {
+  if !mix::Word::fits_in(value, byte_size) {
+    return Err(format!("{} doesn't fit in a word", value));
+  }
+
+  Ok(mix::Word::from_value_in(value, byte_size))
+}
+
+fn to_address(value: isize, byte_size: isize) -> Result<mix::Address, String> {
+  if value.abs() >= byte_size.pow(2) {
+    return Err(format!("{} doesn't fit in two bytes", value));
+  }
+
+  Ok(mix::Address::from_value_in(value, byte_size))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::computer::load;
+
+  #[test]
+  fn test_parse() {
+    let tests = [
+      ("step", Ok(Command::Step(1))),
+      ("s 10", Ok(Command::Step(10))),
+      ("continue", Ok(Command::Continue)),
+      ("break 1000", Ok(Command::Break(1000, None))),
+      (
+        "b 1000 if rI1 = 17",
+        Ok(Command::Break(1000, Some(String::from("rI1 = 17")))),
+      ),
+      (
+        "print rI3",
+        Ok(Command::Print(Target::Register(Register::I(3)), 1)),
+      ),
+      ("p 2000 5", Ok(Command::Print(Target::Memory(2000), 5))),
+      (
+        "set rA -42",
+        Ok(Command::Set(Target::Register(Register::A), -42)),
+      ),
+      ("set -5 1", Ok(Command::Set(Target::Memory(-5), 1))),
+      ("info devices", Ok(Command::InfoDevices)),
+      ("print rI7", Err(String::from("unknown symbol: RI7"))),
+      (
+        "jump",
+        Err(String::from("unknown command: jump (try help)")),
+      ),
+    ];
+
+    for (line, expected) in tests.iter() {
+      assert_eq!(Command::parse(line), *expected);
+    }
+
+    let mut debugger = Debugger::new();
+    debugger.symbols.insert(String::from("LOOP"), 1003);
+    debugger.symbols.insert(String::from("BUF"), 2000);
+    let tests = [
+      ("break LOOP", Ok(Command::Break(1003, None))),
+      ("p buf+3 2", Ok(Command::Print(Target::Memory(2003), 2))),
+      ("unwatch BUF-1", Ok(Command::Unwatch(1999))),
+      ("b END", Err(String::from("unknown symbol: END"))),
+    ];
+
+    for (line, expected) in tests.iter() {
+      assert_eq!(debugger.parse(line), *expected);
+    }
+    assert_eq!(debugger.address_text(1004), "1004 <LOOP+1>");
+  }
+
+  #[test]
+  fn test_run() {
+    let mut computer = Computer::new();
+    let mut debugger = Debugger::new();
+
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(1),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::INCA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::INCA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::INCA,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+
+    load(&mut computer, &instructions);
+
+    debugger
+      .execute(&mut computer, &Command::Break(2, None))
+      .unwrap();
+    assert_eq!(debugger.run(&mut computer, Some(1)), Stop::Steps);
+    assert_eq!(debugger.run(&mut computer, None), Stop::Breakpoint(2));
+    assert_eq!(computer.accumulator, mix::Word::from_value(2));
+    assert_eq!(debugger.run(&mut computer, None), Stop::Halted);
+    assert_eq!(computer.accumulator, mix::Word::from_value(3));
+    assert_eq!(debugger.fault, None);
+
+    let set_pc = |value| Command::Set(Target::Register(Register::ProgramCounter), value);
+    assert!(debugger.execute(&mut computer, &set_pc(5000)).is_err());
+    assert!(debugger.execute(&mut computer, &set_pc(-1)).is_err());
+    assert_eq!(computer.program_counter, 4);
+
+    // running off the end of memory stops the machine, and trying to carry
+    // on is a fault, however many times we try
+    debugger.execute(&mut computer, &set_pc(3999)).unwrap();
+    debugger.execute(&mut computer, &Command::Step(1)).unwrap();
+    assert_eq!(debugger.fault, None);
+    for _ in 0..2 {
+      debugger.execute(&mut computer, &Command::Step(1)).unwrap();
+      assert!(matches!(
+        debugger.fault,
+        Some(MixFault::AddressOutOfRange { address: 4000, .. })
+      ));
+    }
+
+    debugger
+      .execute(&mut computer, &Command::Set(Target::Memory(2000), -7))
+      .unwrap();
+    assert_eq!(computer.memory[2000].read(), mix::Word::from_value(-7));
+    assert_eq!(
+      debugger.execute(
+        &mut computer,
+        &Command::Print(Target::Register(Register::X), 1)
+      ),
+      Ok(format!(
+        "rX: {}",
+        mix::Word::zero().describe(mix::BINARY_BYTE_SIZE)
+      ))
+    );
+  }
+
+  #[test]
+  fn test_conditional_breakpoint() {
+    let mut computer = Computer::new();
+    let mut debugger = Debugger::new();
+
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(1),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::INC1,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 0,
+        operation: mix::op_codes::JMP,
+      },
+    ];
+
+    load(&mut computer, &instructions);
+
+    let command = Command::parse("break 1 if rI1 = 5").unwrap();
+    debugger.execute(&mut computer, &command).unwrap();
+    assert_eq!(debugger.run(&mut computer, None), Stop::Breakpoint(1));
+    assert_eq!(computer.indexes[0], mix::Address::from_value(5));
+
+    let command = Command::parse("break 0 if CONTENTS(LIMIT) > rI1").unwrap();
+    debugger.execute(&mut computer, &command).unwrap();
+    assert_eq!(
+      debugger.run(&mut computer, None),
+      Stop::BadCondition(0, String::from("unknown symbol: LIMIT"))
+    );
+
+    debugger.symbols.insert(String::from("LIMIT"), 3000);
+    computer.memory[3000].write(mix::Word::from_value(8));
+    assert_eq!(debugger.run(&mut computer, None), Stop::Breakpoint(0));
+    assert_eq!(computer.indexes[0], mix::Address::from_value(6));
+  }
+}
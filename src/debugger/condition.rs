@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::computer::Computer;
+use crate::mix;
+use crate::mixal::{
+  Asterisk, Expression, LiteralConstant, Node, NodeVisitor, Number, Parser, Symbol, Token, WValue,
+};
+
+// a condition is split at the first of these, taking <= over < and so on
+const RELATIONS: [(&str, Relation); 7] = [
+  ("<=", Relation::LessOrEqual),
+  (">=", Relation::GreaterOrEqual),
+  ("!=", Relation::NotEqual),
+  ("<>", Relation::NotEqual),
+  ("=", Relation::Equal),
+  ("<", Relation::Less),
+  (">", Relation::Greater),
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Relation {
+  Equal,
+  NotEqual,
+  Less,
+  LessOrEqual,
+  Greater,
+  GreaterOrEqual,
+}
+
+enum Operand {
+  Expression(Box<dyn Node>),
+  Contents(Box<dyn Node>, Option<Box<dyn Node>>),
+}
+
+/// A condition on a breakpoint, such as `rI1 = 17`, `CONTENTS(BUF+3)(1:2) > 5`
+/// or `OVERFLOW`. Each side is a MIXAL expression, where the registers can be
+/// used as symbols (rA, rX, rI1-rI6, rJ and OVERFLOW), `*` is the program
+/// counter, and `CONTENTS(address)` or `CONTENTS(address)(F)` reads memory.
+/// Without a relation, the condition holds if the value isn't zero.
+pub struct Condition {
+  source: String,
+  left: Operand,
+  right: Option<(Relation, Operand)>,
+}
+
+impl Condition {
+  pub fn parse(source: &str) -> Result<Condition, String> {
+    // MIXAL doesn't allow spaces in expressions, or lower case
+    let text: String = source
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .collect::<String>()
+      .to_uppercase();
+
+    let split = RELATIONS
+      .iter()
+      .filter_map(|(symbol, relation)| text.find(symbol).map(|i| (i, *symbol, *relation)))
+      .min_by_key(|(i, symbol, _)| (*i, -(symbol.len() as isize)));
+
+    let (left, right) = match split {
+      Some((i, symbol, relation)) => {
+        let right = parse_operand(&text[i + symbol.len()..])?;
+        (parse_operand(&text[..i])?, Some((relation, right)))
+      }
+      None => (parse_operand(&text)?, None),
+    };
+
+    Ok(Condition {
+      source: String::from(source.trim()),
+      left,
+      right,
+    })
+  }
+
+  pub fn evaluate(
+    &self,
+    computer: &Computer,
+    symbols: &HashMap<String, isize>,
+  ) -> Result<bool, String> {
+    let left = evaluate_operand(&self.left, computer, symbols)?;
+    let (relation, right) = match &self.right {
+      None => return Ok(left != 0),
+      Some((relation, right)) => (relation, evaluate_operand(right, computer, symbols)?),
+    };
+
+    Ok(match relation {
+      Relation::Equal => left == right,
+      Relation::NotEqual => left != right,
+      Relation::Less => left < right,
+      Relation::LessOrEqual => left <= right,
+      Relation::Greater => left > right,
+      Relation::GreaterOrEqual => left >= right,
+    })
+  }
+}
+
+impl fmt::Display for Condition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.source)
+  }
+}
+
+fn parse_operand(text: &str) -> Result<Operand, String> {
+  let inner = match text.strip_prefix("CONTENTS(") {
+    Some(inner) => inner,
+    None => return parse_expression(text).map(Operand::Expression),
+  };
+
+  // find the bracket that closes CONTENTS(
+  let mut depth = 1;
+  let close = inner.find(|c| {
+    match c {
+      '(' => depth += 1,
+      ')' => depth -= 1,
+      _ => {}
+    }
+    depth == 0
+  });
+  let close = close.ok_or("expected closing bracket after CONTENTS")?;
+
+  let address = parse_expression(&inner[..close])?;
+  let rest = &inner[close + 1..];
+  let field = match rest.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+    Some(field) => Some(parse_expression(field)?),
+    None if rest.is_empty() => None,
+    None => return Err(format!("unexpected {} after CONTENTS", rest)),
+  };
+
+  Ok(Operand::Contents(address, field))
+}
+
+/// Works out an address such as `BUF+3` from the symbols, without any
+/// registers.
+pub(super) fn address(text: &str, symbols: &HashMap<String, isize>) -> Result<isize, String> {
+  let expression = parse_expression(&text.to_uppercase())?;
+  let mut evaluator = Evaluator {
+    computer: None,
+    symbols,
+    error: None,
+  };
+
+  let value = expression.accept(&mut evaluator);
+  match evaluator.error {
+    Some(error) => Err(error),
+    None => Ok(value),
+  }
+}
+
+fn parse_expression(text: &str) -> Result<Box<dyn Node>, String> {
+  // the lexer takes a * in the first column to be a comment
+  Parser::new(&format!(" {}", text))
+    .parse_single_expression()
+    .map_err(|e| format!("{}: {}", e, text))
+}
+
+fn evaluate_operand(
+  operand: &Operand,
+  computer: &Computer,
+  symbols: &HashMap<String, isize>,
+) -> Result<isize, String> {
+  let mut evaluator = Evaluator {
+    computer: Some(computer),
+    symbols,
+    error: None,
+  };
+
+  let value = match operand {
+    Operand::Expression(expression) => expression.accept(&mut evaluator),
+    Operand::Contents(address, field) => {
+      let address = address.accept(&mut evaluator);
+      let field = field.as_ref().map(|field| field.accept(&mut evaluator));
+      if let Some(error) = evaluator.error {
+        return Err(error);
+      }
+      return contents(computer, address, field);
+    }
+  };
+
+  match evaluator.error {
+    Some(error) => Err(error),
+    None => Ok(value),
+  }
+}
+
+fn contents(computer: &Computer, address: isize, field: Option<isize>) -> Result<isize, String> {
+  let byte_size = computer.byte_size;
+  let word = super::cell(computer, address)?
+    .try_read()
+    .ok_or_else(|| format!("{:04} is in use by an I/O device", address))?;
+
+  let field = match field {
+    None => return Ok(word.value_in(byte_size)),
+    Some(field) => field,
+  };
+
+  let valid = (0..64).contains(&field) && {
+    let (left, right) = mix::decode_field_spec(field as u8);
+    left <= right && right <= 5
+  };
+  if !valid {
+    return Err(format!("invalid field specification: {}", field));
+  }
+
+  Ok(word.apply_field_spec(field as u8).value_in(byte_size))
+}
+
+struct Evaluator<'a> {
+  // None when there's no machine to read the registers from
+  computer: Option<&'a Computer>,
+  symbols: &'a HashMap<String, isize>,
+  error: Option<String>,
+}
+
+impl<'a> Evaluator<'a> {
+  fn fail(&mut self, message: String) -> isize {
+    if self.error.is_none() {
+      self.error = Some(message);
+    }
+    0
+  }
+}
+
+impl<'a> NodeVisitor for Evaluator<'a> {
+  fn visit_number(&mut self, number: &Number) -> isize {
+    number.value as isize
+  }
+
+  fn visit_asterisk(&mut self, _: &Asterisk) -> isize {
+    match self.computer {
+      Some(computer) => computer.program_counter,
+      None => self.fail(String::from("* can't be used here")),
+    }
+  }
+
+  fn visit_symbol(&mut self, symbol: &Symbol) -> isize {
+    let name = symbol.internal_name();
+    match (name.as_str(), self.computer) {
+      ("RA", Some(computer)) => computer.accumulator.value_in(computer.byte_size),
+      ("RX", Some(computer)) => computer.extension.read().value_in(computer.byte_size),
+      ("RI1" | "RI2" | "RI3" | "RI4" | "RI5" | "RI6", Some(computer)) => {
+        let index = name[2..].parse::<usize>().unwrap();
+        computer.indexes[index - 1].value_in(computer.byte_size)
+      }
+      ("RJ", Some(computer)) => computer.jump_address.value_in(computer.byte_size),
+      ("OVERFLOW", Some(computer)) => computer.overflow as isize,
+      _ => match self.symbols.get(&name) {
+        Some(value) => *value,
+        None => self.fail(format!("unknown symbol: {}", name)),
+      },
+    }
+  }
+
+  fn visit_literal_constant(&mut self, _: &LiteralConstant) -> isize {
+    self.fail(String::from(
+      "literal constants can't be used in conditions",
+    ))
+  }
+
+  fn visit_expression(&mut self, expression: &Expression) -> isize {
+    let left = match &expression.left {
+      None => 0,
+      Some(left) => left.accept(self),
+    };
+
+    let right = expression.right.accept(self);
+
+    match expression.operator {
+      Token::PLUS => left + right,
+      Token::MINUS => left - right,
+      Token::ASTERISK => left * right,
+      Token::DIVIDE if right != 0 => left / right,
+      Token::DIVIDE => self.fail(String::from("division by zero")),
+      Token::FIELDSIGN => 8 * left + right,
+      _ => self.fail(format!(
+        "{:?} can't be used in conditions",
+        expression.operator
+      )),
+    }
+  }
+
+  fn visit_w_value(&mut self, _: &WValue) -> isize {
+    self.fail(String::from("W-values can't be used in conditions"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_condition() {
+    let mut computer = Computer::new();
+    computer.memory[1003].write(mix::Word {
+      bytes: [0, 6, 1, 2, 3],
+      sign: mix::Sign::Negative,
+    });
+    computer.indexes[0] = mix::Address::from_value(17);
+    computer.program_counter = 1500;
+
+    let mut symbols = HashMap::new();
+    symbols.insert(String::from("BUF"), 1000);
+
+    let tests = [
+      ("rI1 = 17", Ok(true)),
+      ("rI1 != 17", Ok(false)),
+      ("RI1+1 >= 18", Ok(true)),
+      ("CONTENTS(BUF+3)(1:2) > 5", Ok(true)),
+      ("CONTENTS(BUF+3)(1:2) <= 5", Ok(false)),
+      ("CONTENTS(BUF + 3) < 0", Ok(true)),
+      ("OVERFLOW", Ok(false)),
+      ("* = 1500", Ok(true)),
+      ("rA = LOOP", Err(String::from("unknown symbol: LOOP"))),
+      (
+        "CONTENTS(4000) = 0",
+        Err(String::from("address out of range: 4000")),
+      ),
+    ];
+
+    for (source, expected) in tests.iter() {
+      let condition = Condition::parse(source).unwrap();
+      assert_eq!(
+        condition.evaluate(&computer, &symbols),
+        *expected,
+        "{}",
+        source
+      );
+    }
+
+    assert!(Condition::parse("rA = ").is_err());
+    assert!(Condition::parse("CONTENTS(1000").is_err());
+  }
+}
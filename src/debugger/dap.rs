@@ -0,0 +1,685 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use serde_json::{json, Value};
+
+use super::{cell, register_value, Condition, Debugger, Register, Stop};
+
+use crate::computer::{Computer, ComputerBuilder};
+use crate::mix::disasm::{self, Labels};
+use crate::mixal;
+
+// there's only ever one thread, and one frame on its stack
+const THREAD: i64 = 1;
+const FRAME: i64 = 1;
+
+// variable references for the scopes
+const REGISTERS: i64 = 1;
+const MEMORY: i64 = 2;
+
+// how many instructions a `continue` runs between looking for requests
+const SLICE: usize = 10_000;
+
+const NOT_LAUNCHED: &str = "there's no program until a launch request";
+
+const REGISTER_NAMES: [(&str, Register); 12] = [
+  ("rA", Register::A),
+  ("rX", Register::X),
+  ("rI1", Register::I(1)),
+  ("rI2", Register::I(2)),
+  ("rI3", Register::I(3)),
+  ("rI4", Register::I(4)),
+  ("rI5", Register::I(5)),
+  ("rI6", Register::I(6)),
+  ("rJ", Register::J),
+  ("PC", Register::ProgramCounter),
+  ("OV", Register::Overflow),
+  ("CI", Register::Comparison),
+];
+
+/// Speaks the Debug Adapter Protocol on `input` and `output` until the
+/// client disconnects. A `launch` request assembles the MIXAL file given as
+/// `program` for a machine with bytes of `byte_size`, and stepping goes one
+/// instruction at a time.
+///
+/// `continue` runs the program in slices, reading requests in between, so
+/// that the client can `pause` it. Requests that need the program to be
+/// stopped, such as `stackTrace`, wait until it is, and so does everything
+/// after them.
+pub fn serve<R: BufRead + Send + 'static, W: Write>(
+  input: R,
+  output: W,
+  byte_size: isize,
+) -> Result<(), String> {
+  let requests = read_messages(input);
+  let mut session = Session::new(output, byte_size);
+  // the first request that arrived while the program was running and that
+  // has to wait for it to stop
+  let mut waiting = None;
+  while !session.done {
+    if session.running {
+      if waiting.is_none() {
+        match requests.try_recv() {
+          Ok(request) => {
+            let request = request?;
+            if answered_while_running(&request) {
+              session.handle(&request)?;
+            } else {
+              waiting = Some(request);
+            }
+            continue;
+          }
+          Err(TryRecvError::Empty) => {}
+          Err(TryRecvError::Disconnected) => break,
+        }
+      }
+      session.run_slice()?;
+      continue;
+    }
+
+    let request = match waiting.take() {
+      Some(request) => request,
+      None => match requests.recv() {
+        Ok(request) => request?,
+        Err(_) => break,
+      },
+    };
+    session.handle(&request)?;
+  }
+
+  Ok(())
+}
+
+/// Reads messages on another thread, so that they can be looked for in
+/// between slices of a run. The channel closes after the end of the input
+/// or the first error.
+fn read_messages<R: BufRead + Send + 'static>(mut input: R) -> Receiver<Result<Value, String>> {
+  let (sender, receiver) = mpsc::channel();
+  thread::spawn(move || loop {
+    let message = match read_message(&mut input) {
+      Ok(Some(message)) => Ok(message),
+      Ok(None) => break,
+      Err(message) => Err(message),
+    };
+    let failed = message.is_err();
+    if sender.send(message).is_err() || failed {
+      break;
+    }
+  });
+
+  receiver
+}
+
+fn answered_while_running(request: &Value) -> bool {
+  let command = request["command"].as_str().unwrap_or("");
+  ["pause", "disconnect", "terminate", "threads"].contains(&command)
+}
+
+/// Reads a message with its `Content-Length` header, or None at the end of
+/// the input.
+fn read_message<R: BufRead>(input: &mut R) -> Result<Option<Value>, String> {
+  let mut length = None;
+  loop {
+    let mut line = String::new();
+    if input.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+      return Ok(None);
+    }
+
+    let line = line.trim();
+    if line.is_empty() {
+      break;
+    }
+    if let Some(value) = line.strip_prefix("Content-Length:") {
+      length = Some(value.trim().parse::<usize>().map_err(|e| e.to_string())?);
+    }
+  }
+
+  let length = length.ok_or("message without a Content-Length")?;
+  let mut body = vec![0; length];
+  input.read_exact(&mut body).map_err(|e| e.to_string())?;
+  serde_json::from_slice(&body)
+    .map(Some)
+    .map_err(|e| e.to_string())
+}
+
+struct Session<W: Write> {
+  output: W,
+  seq: i64,
+  // filled in by `launch`
+  computer: Option<Computer>,
+  debugger: Debugger,
+  // the MIXAL file that was launched
+  source: Option<String>,
+  stop_on_entry: bool,
+  // in the middle of a `continue`
+  running: bool,
+  done: bool,
+  // for the programs that get launched
+  byte_size: isize,
+}
+
+impl<W: Write> Session<W> {
+  fn new(output: W, byte_size: isize) -> Session<W> {
+    Session {
+      output,
+      seq: 0,
+      computer: None,
+      debugger: Debugger::new(),
+      source: None,
+      stop_on_entry: false,
+      running: false,
+      done: false,
+      byte_size,
+    }
+  }
+
+  fn send(&mut self, mut message: Value) -> Result<(), String> {
+    self.seq += 1;
+    message["seq"] = json!(self.seq);
+
+    let body = message.to_string();
+    write!(
+      self.output,
+      "Content-Length: {}\r\n\r\n{}",
+      body.len(),
+      body
+    )
+    .and_then(|_| self.output.flush())
+    .map_err(|e| e.to_string())
+  }
+
+  fn event(&mut self, event: &str, body: Value) -> Result<(), String> {
+    self.send(json!({"type": "event", "event": event, "body": body}))
+  }
+
+  /// Answers a request, then sends any events that follow from it. Errors
+  /// in the request go back to the client; only a broken output is an Err.
+  fn handle(&mut self, request: &Value) -> Result<(), String> {
+    let command = request["command"].as_str().unwrap_or("");
+    let arguments = &request["arguments"];
+
+    let (body, stop) = match self.respond(command, arguments) {
+      Ok(result) => result,
+      Err(message) => {
+        return self.send(json!({
+          "type": "response",
+          "request_seq": request["seq"],
+          "command": command,
+          "success": false,
+          "message": message,
+        }));
+      }
+    };
+
+    self.send(json!({
+      "type": "response",
+      "request_seq": request["seq"],
+      "command": command,
+      "success": true,
+      "body": body,
+    }))?;
+
+    if command == "initialize" {
+      self.event("initialized", json!({}))?;
+    }
+    if command == "pause" {
+      self.event("stopped", json!({"reason": "pause", "threadId": THREAD}))?;
+    }
+    match stop {
+      Some(stop) => self.stopped(stop),
+      None => Ok(()),
+    }
+  }
+
+  fn respond(&mut self, command: &str, arguments: &Value) -> Result<(Value, Option<Stop>), String> {
+    let body = match command {
+      "initialize" => json!({
+        "supportsConfigurationDoneRequest": true,
+        "supportsConditionalBreakpoints": true,
+        "supportsStepBack": true,
+      }),
+      "launch" => {
+        self.launch(arguments)?;
+        json!({})
+      }
+      "setBreakpoints" => self.set_breakpoints(arguments)?,
+      "setExceptionBreakpoints" => json!({}),
+      "configurationDone" => {
+        let computer = self.computer.as_ref().ok_or(NOT_LAUNCHED)?;
+        if self.stop_on_entry {
+          self.event("stopped", json!({"reason": "entry", "threadId": THREAD}))?;
+          return Ok((json!({}), None));
+        }
+        // running only checks breakpoints after the first instruction
+        let entry = computer.program_counter;
+        let stop = match self.debugger.at_breakpoint(computer) {
+          Ok(true) => Some(Stop::Breakpoint(entry)),
+          Ok(false) => {
+            self.running = true;
+            None
+          }
+          Err(message) => Some(Stop::BadCondition(entry, message)),
+        };
+        return Ok((json!({}), stop));
+      }
+      "threads" => json!({"threads": [{"id": THREAD, "name": "MIX"}]}),
+      "stackTrace" => self.stack_trace()?,
+      "scopes" => json!({"scopes": [
+        {"name": "Registers", "variablesReference": REGISTERS, "expensive": false},
+        {"name": "Memory", "variablesReference": MEMORY, "expensive": false},
+      ]}),
+      "variables" => self.variables(arguments["variablesReference"].as_i64())?,
+      "next" | "stepIn" | "stepOut" => {
+        let computer = self.computer.as_mut().ok_or(NOT_LAUNCHED)?;
+        let stop = self.debugger.run(computer, Some(1));
+        return Ok((json!({}), Some(stop)));
+      }
+      "continue" => {
+        self.computer.as_ref().ok_or(NOT_LAUNCHED)?;
+        self.running = true;
+        json!({"allThreadsContinued": true})
+      }
+      "pause" => {
+        if !self.running {
+          return Err(String::from("the program isn't running"));
+        }
+        self.running = false;
+        json!({})
+      }
+      "stepBack" => {
+        let computer = self.computer.as_mut().ok_or(NOT_LAUNCHED)?;
+        if !computer.step_back() {
+          return Err(String::from("no more history"));
+        }
+        return Ok((json!({}), Some(Stop::Steps)));
+      }
+      "reverseContinue" => {
+        let computer = self.computer.as_mut().ok_or(NOT_LAUNCHED)?;
+        let debugger = &self.debugger;
+        let stop = if computer.reverse_continue(|c| debugger.at_breakpoint(c).unwrap_or(true)) {
+          Stop::Breakpoint(computer.program_counter)
+        } else {
+          Stop::Steps
+        };
+        return Ok((json!({}), Some(stop)));
+      }
+      "evaluate" => {
+        // the same commands as the console debugger, such as `print BUF+3`
+        let computer = self.computer.as_mut().ok_or(NOT_LAUNCHED)?;
+        let expression = arguments["expression"].as_str().unwrap_or("");
+        let command = self.debugger.parse(expression)?;
+        let result = self.debugger.execute(computer, &command)?;
+        json!({"result": result, "variablesReference": 0})
+      }
+      "disconnect" | "terminate" => {
+        self.running = false;
+        self.done = true;
+        json!({})
+      }
+      _ => return Err(format!("{} isn't supported", command)),
+    };
+
+    Ok((body, None))
+  }
+
+  /// Assembles the program and loads it, ready to start. The program is
+  /// assembled in-process, the same way as `shake`.
+  fn launch(&mut self, arguments: &Value) -> Result<(), String> {
+    let path = arguments["program"]
+      .as_str()
+      .ok_or("launch needs a program to run")?;
+    let source = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+
+    let program = mixal::Parser::new(&source).parse()?;
+    let mut assembler = mixal::Assembler::with_byte_size(self.byte_size)?;
+    assembler.assemble(program)?;
+
+    let mut computer = ComputerBuilder::new().assembled(&assembler).build()?;
+    computer.running = true;
+    let history = arguments["history"].as_u64().unwrap_or(1000);
+    computer.enable_history(history as usize);
+
+    let info = assembler.debug_info();
+    self.debugger.symbols = info.symbols;
+    self.debugger.lines = info.lines;
+    self.computer = Some(computer);
+    self.source = Some(String::from(path));
+    self.stop_on_entry = arguments["stopOnEntry"].as_bool().unwrap_or(false);
+
+    Ok(())
+  }
+
+  /// Sets breakpoints by source line, moving each one down to the next line
+  /// that was assembled into memory.
+  fn set_breakpoints(&mut self, arguments: &Value) -> Result<Value, String> {
+    self.debugger.breakpoints.clear();
+
+    let requested = arguments["breakpoints"]
+      .as_array()
+      .cloned()
+      .unwrap_or_default();
+    let mut breakpoints = Vec::new();
+    for breakpoint in requested.iter() {
+      let line = breakpoint["line"].as_u64().unwrap_or(0) as usize;
+      let location = self
+        .debugger
+        .lines
+        .iter()
+        .filter(|(_, l)| **l >= line)
+        .min_by_key(|(location, l)| (**l, **location));
+
+      let (location, line) = match location {
+        Some((location, line)) => (*location as isize, *line),
+        None => {
+          breakpoints.push(json!({"verified": false, "line": line}));
+          continue;
+        }
+      };
+
+      let condition = match breakpoint["condition"].as_str() {
+        Some(condition) => Some(Condition::parse(condition)?),
+        None => None,
+      };
+      self.debugger.breakpoints.insert(location, condition);
+      breakpoints.push(json!({"verified": true, "line": line}));
+    }
+
+    Ok(json!({ "breakpoints": breakpoints }))
+  }
+
+  /// Runs part of a `continue`, stopping early at anything that would stop
+  /// the console debugger.
+  fn run_slice(&mut self) -> Result<(), String> {
+    let computer = self.computer.as_mut().ok_or(NOT_LAUNCHED)?;
+    match self.debugger.run(computer, Some(SLICE)) {
+      Stop::Steps => Ok(()),
+      stop => {
+        self.running = false;
+        self.stopped(stop)
+      }
+    }
+  }
+
+  fn stack_trace(&self) -> Result<Value, String> {
+    let pc = self.computer.as_ref().ok_or(NOT_LAUNCHED)?.program_counter;
+    let line = self
+      .debugger
+      .lines
+      .get(&(pc as usize))
+      .copied()
+      .unwrap_or(0);
+    let name = self
+      .debugger
+      .symbols
+      .label(pc)
+      .unwrap_or_else(|| format!("{:04}", pc));
+
+    let mut frame = json!({
+      "id": FRAME,
+      "name": name,
+      "line": line,
+      "column": 1,
+      "instructionPointerReference": pc.to_string(),
+    });
+    if let Some(path) = &self.source {
+      frame["source"] = json!({ "path": path });
+    }
+
+    Ok(json!({"stackFrames": [frame], "totalFrames": 1}))
+  }
+
+  /// The registers, or the memory that the program was assembled into.
+  fn variables(&self, reference: Option<i64>) -> Result<Value, String> {
+    let computer = self.computer.as_ref().ok_or(NOT_LAUNCHED)?;
+    let variables: Vec<Value> = match reference {
+      Some(REGISTERS) => REGISTER_NAMES
+        .iter()
+        .map(|(name, register)| {
+          let value = register_value(computer, *register);
+          json!({"name": name, "value": value.to_string(), "variablesReference": 0})
+        })
+        .collect(),
+      Some(MEMORY) => {
+        let locations: BTreeSet<&usize> = self.debugger.lines.keys().collect();
+        let labels: &dyn Labels = &self.debugger.symbols;
+        locations
+          .into_iter()
+          .filter_map(|location| {
+            let address = *location as isize;
+            let word = cell(computer, address).ok()?.try_read()?;
+            let byte_size = computer.byte_size;
+            let text = disasm::to_mixal_with_labels(word, Some(address), Some(labels), byte_size);
+            Some(json!({
+              "name": self.debugger.address_text(address),
+              "value": format!("{}  {}", word.value_in(byte_size), text),
+              "variablesReference": 0,
+            }))
+          })
+          .collect()
+      }
+      _ => return Err(String::from("unknown variables reference")),
+    };
+
+    Ok(json!({ "variables": variables }))
+  }
+
+  fn stopped(&mut self, stop: Stop) -> Result<(), String> {
+    let reason = match stop {
+      Stop::Steps => "step",
+      Stop::Breakpoint(_) => "breakpoint",
+      Stop::BadCondition(_, message) => {
+        let output = format!("breakpoint condition failed: {}\n", message);
+        self.event("output", json!({"category": "stderr", "output": output}))?;
+        "breakpoint"
+      }
+      Stop::Watchpoint(_) => "data breakpoint",
+      Stop::Halted => {
+        self.event("exited", json!({"exitCode": 0}))?;
+        return self.event("terminated", json!({}));
+      }
+      Stop::Fault(fault) => {
+        let text = format!("{}", fault);
+        return self.event(
+          "stopped",
+          json!({"reason": "exception", "threadId": THREAD, "text": text}),
+        );
+      }
+    };
+
+    self.event("stopped", json!({"reason": reason, "threadId": THREAD}))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Cursor;
+
+  use crate::mix;
+
+  fn request(seq: i64, command: &str, arguments: Value) -> String {
+    let body = json!({"seq": seq, "type": "request", "command": command, "arguments": arguments})
+      .to_string();
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+  }
+
+  fn messages(output: &[u8]) -> Vec<Value> {
+    let mut output = Cursor::new(output);
+    let mut messages = Vec::new();
+    while let Some(message) = read_message(&mut output).unwrap() {
+      messages.push(message);
+    }
+    messages
+  }
+
+  #[test]
+  fn test_session() {
+    let path = std::env::temp_dir().join("negroni-dap-test.mixal");
+    fs::write(
+      &path,
+      "\
+* a test program
+         ORIG 1000
+START    ENTA 5
+         STA  2000
+         HLT
+         END  START
+",
+    )
+    .unwrap();
+    let path = path.to_str().unwrap();
+
+    let input = [
+      request(1, "initialize", json!({})),
+      request(2, "launch", json!({ "program": path })),
+      request(
+        3,
+        "setBreakpoints",
+        json!({"breakpoints": [{"line": 2}, {"line": 4}, {"line": 9}]}),
+      ),
+      request(4, "configurationDone", json!({})),
+      request(5, "stackTrace", json!({"threadId": 1})),
+      request(6, "continue", json!({"threadId": 1})),
+      request(7, "stackTrace", json!({"threadId": 1})),
+      request(8, "variables", json!({"variablesReference": REGISTERS})),
+      request(9, "next", json!({"threadId": 1})),
+      request(10, "stepBack", json!({"threadId": 1})),
+      request(11, "continue", json!({"threadId": 1})),
+      // this waits for the program to halt, and the disconnect waits for it
+      request(12, "threads", json!({})),
+      request(13, "stackTrace", json!({"threadId": 1})),
+      request(14, "disconnect", json!({})),
+    ]
+    .concat();
+
+    let mut output = Vec::new();
+    serve(Cursor::new(input), &mut output, mix::BINARY_BYTE_SIZE).unwrap();
+    let messages = messages(&output);
+
+    let responses: Vec<&Value> = messages
+      .iter()
+      .filter(|m| m["type"] == "response")
+      .collect();
+    assert_eq!(responses.len(), 14);
+    assert!(
+      responses.iter().all(|r| r["success"] == true),
+      "{:?}",
+      responses
+    );
+
+    let breakpoints = &responses[2]["body"]["breakpoints"];
+    assert_eq!(breakpoints[0], json!({"verified": true, "line": 3}));
+    assert_eq!(breakpoints[1], json!({"verified": true, "line": 4}));
+    assert_eq!(breakpoints[2]["verified"], false);
+
+    // the breakpoint on the first instruction stops the program before it
+    // runs
+    assert_eq!(responses[4]["body"]["stackFrames"][0]["line"], 3);
+    assert_eq!(responses[6]["body"]["stackFrames"][0]["line"], 4);
+    assert_eq!(responses[6]["body"]["stackFrames"][0]["name"], "START+1");
+    assert_eq!(
+      responses[7]["body"]["variables"][0],
+      json!({"name": "rA", "value": "5", "variablesReference": 0})
+    );
+
+    let events: Vec<&str> = messages
+      .iter()
+      .filter(|m| m["type"] == "event")
+      .map(|m| m["event"].as_str().unwrap())
+      .collect();
+    assert_eq!(
+      events,
+      vec![
+        "initialized",
+        "stopped",
+        "stopped",
+        "stopped",
+        "stopped",
+        "exited",
+        "terminated"
+      ]
+    );
+    let stopped = messages.iter().find(|m| m["event"] == "stopped").unwrap();
+    assert_eq!(stopped["body"]["reason"], "breakpoint");
+  }
+
+  #[test]
+  fn test_before_launch() {
+    let input = [
+      request(1, "initialize", json!({})),
+      request(2, "stackTrace", json!({"threadId": 1})),
+      request(3, "continue", json!({"threadId": 1})),
+      request(4, "configurationDone", json!({})),
+      request(5, "disconnect", json!({})),
+    ]
+    .concat();
+
+    let mut output = Vec::new();
+    serve(Cursor::new(input), &mut output, mix::BINARY_BYTE_SIZE).unwrap();
+    let messages = messages(&output);
+
+    let responses: Vec<&Value> = messages
+      .iter()
+      .filter(|m| m["type"] == "response")
+      .collect();
+    assert_eq!(responses.len(), 5);
+    for response in responses[1..4].iter() {
+      assert_eq!(response["success"], false);
+      assert_eq!(response["message"], NOT_LAUNCHED);
+    }
+    assert_eq!(responses[4]["success"], true);
+  }
+
+  #[test]
+  fn test_pause() {
+    let path = std::env::temp_dir().join("negroni-dap-pause-test.mixal");
+    fs::write(
+      &path,
+      "\
+* a program that never halts
+         ORIG 1000
+START    JMP  START
+         END  START
+",
+    )
+    .unwrap();
+    let path = path.to_str().unwrap();
+
+    let input = [
+      request(1, "initialize", json!({})),
+      request(2, "launch", json!({ "program": path })),
+      request(3, "configurationDone", json!({})),
+      request(4, "pause", json!({"threadId": 1})),
+      request(5, "stackTrace", json!({"threadId": 1})),
+      request(6, "disconnect", json!({})),
+    ]
+    .concat();
+
+    let mut output = Vec::new();
+    serve(Cursor::new(input), &mut output, mix::BINARY_BYTE_SIZE).unwrap();
+    let messages = messages(&output);
+
+    let responses: Vec<&Value> = messages
+      .iter()
+      .filter(|m| m["type"] == "response")
+      .collect();
+    assert_eq!(responses.len(), 6);
+    assert!(
+      responses.iter().all(|r| r["success"] == true),
+      "{:?}",
+      responses
+    );
+    assert_eq!(responses[4]["body"]["stackFrames"][0]["line"], 3);
+
+    let stopped: Vec<&Value> = messages
+      .iter()
+      .filter(|m| m["event"] == "stopped")
+      .collect();
+    assert_eq!(stopped.len(), 1);
+    assert_eq!(stopped[0]["body"]["reason"], "pause");
+  }
+}
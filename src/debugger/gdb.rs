@@ -0,0 +1,569 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+use super::{cell, register_value, write_register, Debugger, Register, Stop};
+
+use crate::computer::{Access, Computer, WatchKind, Watchpoint};
+use crate::mix;
+
+// GDB addresses bytes, so each word shows up as eight of them, holding its
+// value as a little-endian 64-bit integer; a decimal word needs more than 32
+// bits. Memory addresses and the program counter are in these bytes, so 1000
+// in MIX is 0x1f40 in GDB.
+const WORD: usize = 8;
+
+// how many instructions a continue runs between looking for an interrupt
+const SLICE: usize = 10_000;
+
+// what GDB sends to stop a running program
+const INTERRUPT: u8 = 0x03;
+
+const REGISTERS: [Register; 12] = [
+  Register::A,
+  Register::X,
+  Register::I(1),
+  Register::I(2),
+  Register::I(3),
+  Register::I(4),
+  Register::I(5),
+  Register::I(6),
+  Register::J,
+  Register::ProgramCounter,
+  Register::Overflow,
+  Register::Comparison,
+];
+
+const TARGET_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0">
+  <feature name="org.negroni.mix">
+    <reg name="rA" bitsize="64" type="int64" regnum="0"/>
+    <reg name="rX" bitsize="64" type="int64"/>
+    <reg name="rI1" bitsize="64" type="int64"/>
+    <reg name="rI2" bitsize="64" type="int64"/>
+    <reg name="rI3" bitsize="64" type="int64"/>
+    <reg name="rI4" bitsize="64" type="int64"/>
+    <reg name="rI5" bitsize="64" type="int64"/>
+    <reg name="rI6" bitsize="64" type="int64"/>
+    <reg name="rJ" bitsize="64" type="int64"/>
+    <reg name="pc" bitsize="64" type="code_ptr"/>
+    <reg name="overflow" bitsize="64" type="int64"/>
+    <reg name="comparison" bitsize="64" type="int64"/>
+  </feature>
+</target>
+"#;
+
+/// Waits for GDB to connect to `port` on localhost, then debugs `computer`
+/// until GDB detaches or kills it. GDB can interrupt a continue.
+pub fn serve(computer: &mut Computer, debugger: &mut Debugger, port: u16) -> Result<(), String> {
+  let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+  let (stream, _) = listener.accept().map_err(|e| e.to_string())?;
+  let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+  let mut session = Session::new(computer, debugger);
+
+  while let Some(packet) = read_packet(&mut reader)? {
+    let reply = match packet {
+      Ok(packet) => {
+        send(&stream, "+")?;
+        session.handle(&packet, &mut || interrupted(&mut reader))
+      }
+      Err(_) => {
+        send(&stream, "-")?;
+        continue;
+      }
+    };
+    send(&stream, &frame(&reply))?;
+    if session.done {
+      break;
+    }
+  }
+
+  Ok(())
+}
+
+/// Looks for an interrupt from GDB without waiting for one. Errors are left
+/// for the next read to find.
+fn interrupted(reader: &mut BufReader<TcpStream>) -> bool {
+  if reader.get_ref().set_nonblocking(true).is_err() {
+    return false;
+  }
+  let found = match reader.fill_buf() {
+    Ok(buffer) => buffer.first() == Some(&INTERRUPT),
+    Err(_) => false,
+  };
+  if found {
+    reader.consume(1);
+  }
+
+  reader.get_ref().set_nonblocking(false).is_ok() && found
+}
+
+fn send(mut stream: &TcpStream, text: &str) -> Result<(), String> {
+  stream.write_all(text.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Reads the next `$packet#checksum`, skipping acknowledgements. Gives None
+/// at the end of the stream, and Err inside if the checksum is wrong.
+fn read_packet<R: BufRead>(reader: &mut R) -> Result<Option<Result<String, ()>>, String> {
+  let mut skipped = Vec::new();
+  reader
+    .read_until(b'$', &mut skipped)
+    .map_err(|e| e.to_string())?;
+  if skipped.last() != Some(&b'$') {
+    return Ok(None);
+  }
+
+  let mut data = Vec::new();
+  reader
+    .read_until(b'#', &mut data)
+    .map_err(|e| e.to_string())?;
+  if data.pop() != Some(b'#') {
+    return Ok(None);
+  }
+
+  let mut checksum = [0; 2];
+  reader
+    .read_exact(&mut checksum)
+    .map_err(|e| e.to_string())?;
+  let checksum = std::str::from_utf8(&checksum)
+    .ok()
+    .and_then(|text| u8::from_str_radix(text, 16).ok());
+
+  let sum = data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+  match (checksum, String::from_utf8(data)) {
+    (Some(checksum), Ok(packet)) if checksum == sum => Ok(Some(Ok(packet))),
+    _ => Ok(Some(Err(()))),
+  }
+}
+
+fn frame(data: &str) -> String {
+  let sum = data.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+  format!("${}#{:02x}", data, sum)
+}
+
+struct Session<'a> {
+  computer: &'a mut Computer,
+  debugger: &'a mut Debugger,
+  done: bool,
+}
+
+impl<'a> Session<'a> {
+  fn new(computer: &'a mut Computer, debugger: &'a mut Debugger) -> Session<'a> {
+    Session {
+      computer,
+      debugger,
+      done: false,
+    }
+  }
+
+  /// Carries out a packet from GDB, returning the reply. Anything we don't
+  /// support gets an empty reply, as the protocol asks. A continue stops
+  /// when `interrupted` says so.
+  fn handle(&mut self, packet: &str, interrupted: &mut dyn FnMut() -> bool) -> String {
+    let command = packet.get(..1).unwrap_or("");
+    let args = packet.get(1..).unwrap_or("");
+    let reply = match command {
+      "?" => Ok(String::from("S05")),
+      "g" => Ok(self.read_registers()),
+      "G" => self.write_registers(args),
+      "p" => self.read_register(args),
+      "P" => self.write_register(args),
+      "m" => self.read_memory(args),
+      "M" => self.write_memory(args),
+      "s" => Ok(self.resume(Some(1), interrupted)),
+      "c" => Ok(self.resume(None, interrupted)),
+      "b" if args == "s" => Ok(self.step_back()),
+      "b" if args == "c" => Ok(self.reverse_continue()),
+      "Z" => self.set_point(args, true),
+      "z" => self.set_point(args, false),
+      "H" => Ok(String::from("OK")),
+      "q" => Ok(self.query(args)),
+      "D" | "k" => {
+        self.done = true;
+        Ok(String::from("OK"))
+      }
+      _ => Ok(String::new()),
+    };
+
+    reply.unwrap_or_else(String::from)
+  }
+
+  fn query(&self, args: &str) -> String {
+    if args.starts_with("Supported") {
+      return String::from("PacketSize=4000;qXfer:features:read+;ReverseStep+;ReverseContinue+");
+    }
+    if let Some(range) = args.strip_prefix("Xfer:features:read:target.xml:") {
+      return match parse_pair(range, ',') {
+        Some((offset, length)) => {
+          let rest = TARGET_XML.get(offset.min(TARGET_XML.len())..).unwrap_or("");
+          if rest.len() > length {
+            format!("m{}", &rest[..length])
+          } else {
+            format!("l{}", rest)
+          }
+        }
+        None => String::from("E01"),
+      };
+    }
+
+    match args {
+      "Attached" => String::from("1"),
+      "C" => String::from("QC1"),
+      "fThreadInfo" => String::from("m1"),
+      "sThreadInfo" => String::from("l"),
+      _ => String::new(),
+    }
+  }
+
+  fn register_value(&self, register: Register) -> isize {
+    match register {
+      Register::ProgramCounter => self.computer.program_counter * WORD as isize,
+      _ => register_value(self.computer, register),
+    }
+  }
+
+  fn set_register_value(&mut self, register: Register, value: isize) -> Result<(), &'static str> {
+    let value = match register {
+      Register::ProgramCounter if value % WORD as isize != 0 => return Err("E01"),
+      Register::ProgramCounter => value / WORD as isize,
+      _ => value,
+    };
+    write_register(self.computer, register, value).map_err(|_| "E01")
+  }
+
+  fn read_registers(&self) -> String {
+    REGISTERS
+      .iter()
+      .map(|register| encode(self.register_value(*register)))
+      .collect()
+  }
+
+  fn write_registers(&mut self, args: &str) -> Result<String, &'static str> {
+    if args.len() != REGISTERS.len() * WORD * 2 {
+      return Err("E01");
+    }
+    for (i, register) in REGISTERS.iter().enumerate() {
+      let value = decode(&args[i * WORD * 2..(i + 1) * WORD * 2]).ok_or("E01")?;
+      self.set_register_value(*register, value)?;
+    }
+    Ok(String::from("OK"))
+  }
+
+  fn read_register(&self, args: &str) -> Result<String, &'static str> {
+    let number = usize::from_str_radix(args, 16).map_err(|_| "E01")?;
+    let register = REGISTERS.get(number).ok_or("E01")?;
+    Ok(encode(self.register_value(*register)))
+  }
+
+  fn write_register(&mut self, args: &str) -> Result<String, &'static str> {
+    let (number, value) = args.split_once('=').ok_or("E01")?;
+    let number = usize::from_str_radix(number, 16).map_err(|_| "E01")?;
+    let register = *REGISTERS.get(number).ok_or("E01")?;
+    self.set_register_value(register, decode(value).ok_or("E01")?)?;
+    Ok(String::from("OK"))
+  }
+
+  fn read_memory(&self, args: &str) -> Result<String, &'static str> {
+    let (start, length) = parse_pair(args, ',').ok_or("E01")?;
+
+    let mut bytes = Vec::new();
+    for address in start / WORD..(start + length).div_ceil(WORD) {
+      let word = cell(self.computer, address as isize)
+        .ok()
+        .and_then(|cell| cell.try_read())
+        .ok_or("E01")?;
+      bytes.extend_from_slice(&(word.value_in(self.computer.byte_size) as i64).to_le_bytes());
+    }
+
+    let skip = start % WORD;
+    Ok(
+      bytes[skip..skip + length]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect(),
+    )
+  }
+
+  /// Only whole words can be written, since a word can't hold any 64-bit
+  /// value.
+  fn write_memory(&mut self, args: &str) -> Result<String, &'static str> {
+    let (range, data) = args.split_once(':').ok_or("E01")?;
+    let (start, length) = parse_pair(range, ',').ok_or("E01")?;
+    if start % WORD != 0 || length % WORD != 0 || data.len() != length * 2 {
+      return Err("E01");
+    }
+
+    let byte_size = self.computer.byte_size;
+    for i in 0..length / WORD {
+      let value = decode(&data[i * WORD * 2..(i + 1) * WORD * 2]).ok_or("E01")?;
+      if !mix::Word::fits_in(value, byte_size) {
+        return Err("E01");
+      }
+      cell(self.computer, (start / WORD + i) as isize)
+        .ok()
+        .and_then(|cell| cell.try_write(mix::Word::from_value_in(value, byte_size)))
+        .ok_or("E01")?;
+    }
+
+    Ok(String::from("OK"))
+  }
+
+  /// Breakpoints (types 0 and 1) and watchpoints (2 for writes, 3 for reads
+  /// and 4 for either).
+  fn set_point(&mut self, args: &str, insert: bool) -> Result<String, &'static str> {
+    let mut parts = args.split(',');
+    let kind = parts.next().ok_or("E01")?;
+    let address = parts
+      .next()
+      .and_then(|a| usize::from_str_radix(a, 16).ok())
+      .ok_or("E01")?;
+    let length = parts
+      .next()
+      .and_then(|l| usize::from_str_radix(l, 16).ok())
+      .ok_or("E01")?;
+    let start = (address / WORD) as isize;
+
+    let watch = match kind {
+      "0" | "1" => {
+        if insert {
+          self.debugger.breakpoints.insert(start, None);
+        } else {
+          self.debugger.breakpoints.remove(&start);
+        }
+        return Ok(String::from("OK"));
+      }
+      "2" => WatchKind::Write,
+      "3" => WatchKind::Read,
+      "4" => WatchKind::Any,
+      _ => return Ok(String::new()),
+    };
+
+    if insert {
+      cell(self.computer, start).map_err(|_| "E01")?;
+      self.computer.watches.add(Watchpoint {
+        start,
+        length: ((address + length).div_ceil(WORD) - address / WORD).max(1),
+        kind: watch,
+      });
+    } else {
+      self.computer.watches.remove(start);
+    }
+    Ok(String::from("OK"))
+  }
+
+  /// Runs the program for `steps`, or without a limit in slices, asking
+  /// `interrupted` in between.
+  fn resume(&mut self, steps: Option<usize>, interrupted: &mut dyn FnMut() -> bool) -> String {
+    let stop = match steps {
+      Some(steps) => self.debugger.run(self.computer, Some(steps)),
+      None => loop {
+        match self.debugger.run(self.computer, Some(SLICE)) {
+          // SIGINT
+          Stop::Steps if interrupted() => return String::from("S02"),
+          Stop::Steps => {}
+          stop => break stop,
+        }
+      },
+    };
+
+    match stop {
+      Stop::Steps | Stop::Breakpoint(_) | Stop::BadCondition(_, _) => String::from("S05"),
+      Stop::Watchpoint(hits) => {
+        let hit = &hits[0];
+        let kind = match hit.access {
+          Access::Read => "rwatch",
+          Access::Write => "watch",
+        };
+        format!("T05{}:{:x};", kind, hit.address as usize * WORD)
+      }
+      Stop::Halted => String::from("W00"),
+      // SIGILL
+      Stop::Fault(_) => String::from("S04"),
+    }
+  }
+
+  fn step_back(&mut self) -> String {
+    if self.computer.step_back() {
+      String::from("S05")
+    } else {
+      String::from("T05replaylog:begin;")
+    }
+  }
+
+  fn reverse_continue(&mut self) -> String {
+    let breakpoints = &self.debugger.breakpoints;
+    if self
+      .computer
+      .reverse_continue(|c| breakpoints.contains_key(&c.program_counter))
+    {
+      String::from("S05")
+    } else {
+      String::from("T05replaylog:begin;")
+    }
+  }
+}
+
+fn parse_pair(text: &str, separator: char) -> Option<(usize, usize)> {
+  let (first, second) = text.split_once(separator)?;
+  Some((
+    usize::from_str_radix(first, 16).ok()?,
+    usize::from_str_radix(second, 16).ok()?,
+  ))
+}
+
+fn encode(value: isize) -> String {
+  (value as i64)
+    .to_le_bytes()
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect()
+}
+
+fn decode(hex: &str) -> Option<isize> {
+  if hex.len() != WORD * 2 {
+    return None;
+  }
+
+  let mut bytes = [0; WORD];
+  for (i, byte) in bytes.iter_mut().enumerate() {
+    *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+  }
+  Some(i64::from_le_bytes(bytes) as isize)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Cursor;
+
+  use crate::computer::{load, ComputerBuilder};
+
+  #[test]
+  fn test_packets() {
+    let mut input = Cursor::new(&b"+$m0,4#fd-$g#00"[..]);
+    assert_eq!(read_packet(&mut input), Ok(Some(Ok(String::from("m0,4")))));
+    assert_eq!(read_packet(&mut input), Ok(Some(Err(()))));
+    assert_eq!(read_packet(&mut input), Ok(None));
+
+    assert_eq!(frame("OK"), "$OK#9a");
+  }
+
+  fn reply(session: &mut Session, packet: &str) -> String {
+    session.handle(packet, &mut || false)
+  }
+
+  #[test]
+  fn test_session() {
+    let mut computer = Computer::new();
+    let mut debugger = Debugger::new();
+    computer.enable_history(10);
+
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(-3),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::ENTA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(2000),
+        index_specification: 0,
+        modification: 5,
+        operation: mix::op_codes::STA,
+      },
+      mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 2,
+        operation: mix::op_codes::HLT,
+      },
+    ];
+    load(&mut computer, &instructions);
+
+    let mut session = Session::new(&mut computer, &mut debugger);
+    assert_eq!(reply(&mut session, "s"), "S05");
+    assert_eq!(reply(&mut session, "p0"), "fdffffffffffffff");
+    assert_eq!(reply(&mut session, "p9"), "0800000000000000");
+
+    // a breakpoint on the HLT, then back to try a watchpoint on 2000
+    assert_eq!(reply(&mut session, "Z0,10,1"), "OK");
+    assert_eq!(reply(&mut session, "c"), "S05");
+    assert_eq!(reply(&mut session, "p9"), "1000000000000000");
+    assert_eq!(reply(&mut session, "m3e80,8"), "fdffffffffffffff");
+    assert_eq!(reply(&mut session, "bs"), "S05");
+    assert_eq!(reply(&mut session, "p9"), "0800000000000000");
+    assert_eq!(reply(&mut session, "Z2,3e80,8"), "OK");
+    assert_eq!(reply(&mut session, "c"), "T05watch:3e80;");
+
+    assert_eq!(reply(&mut session, "M3e88,8:0700000000000000"), "OK");
+    assert_eq!(reply(&mut session, "M3e8a,2:0700"), "E01");
+    assert_eq!(reply(&mut session, "P0=0500000000000000"), "OK");
+    let registers = reply(&mut session, "g");
+    assert_eq!(registers.len(), REGISTERS.len() * WORD * 2);
+
+    // the PC has to stay in memory, which is 32000 bytes
+    assert_eq!(reply(&mut session, "P9=007d000000000000"), "E01");
+    let pc = 9 * WORD * 2;
+    let moved = format!(
+      "G{}007d000000000000{}",
+      &registers[..pc],
+      &registers[pc + WORD * 2..]
+    );
+    assert_eq!(reply(&mut session, &moved), "E01");
+    assert_eq!(reply(&mut session, "p9"), "1000000000000000");
+    assert_eq!(reply(&mut session, "vMustReplyEmpty"), "");
+    assert_eq!(reply(&mut session, "z0,10,1"), "OK");
+    assert_eq!(reply(&mut session, "z2,3e80,8"), "OK");
+    assert_eq!(reply(&mut session, "c"), "W00");
+    assert_eq!(reply(&mut session, "D"), "OK");
+    assert!(session.done);
+
+    assert_eq!(computer.memory[2001].read(), mix::Word::from_value(7));
+    assert_eq!(computer.accumulator, mix::Word::from_value(5));
+  }
+
+  #[test]
+  fn test_decimal() {
+    let mut computer = ComputerBuilder::new().byte_size(100).build().unwrap();
+    let mut debugger = Debugger::new();
+    computer.memory[2000].write(mix::Word::from_value_in(-9_999_999_999, 100));
+
+    let mut session = Session::new(&mut computer, &mut debugger);
+    assert_eq!(reply(&mut session, "m3e80,8"), "011cf4abfdffffff");
+    assert_eq!(reply(&mut session, "M3e88,8:ffe30b5402000000"), "OK");
+    assert_eq!(reply(&mut session, "M3e88,8:00e40b5402000000"), "E01");
+    assert_eq!(
+      computer.memory[2001].read(),
+      mix::Word::from_value_in(9_999_999_999, 100)
+    );
+  }
+
+  #[test]
+  fn test_interrupt() {
+    let mut computer = Computer::new();
+    let mut debugger = Debugger::new();
+    // a JMP to itself, which never halts
+    computer.memory[0].write(mix::Word::from_instruction(&mix::Instruction {
+      address: mix::Address::zero(),
+      index_specification: 0,
+      modification: 0,
+      operation: mix::op_codes::JMP,
+    }));
+
+    let mut session = Session::new(&mut computer, &mut debugger);
+    assert_eq!(session.handle("c", &mut || true), "S02");
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream);
+    assert!(!interrupted(&mut reader));
+
+    client.write_all(&[INTERRUPT]).unwrap();
+    while !interrupted(&mut reader) {
+      std::thread::yield_now();
+    }
+    assert!(!interrupted(&mut reader));
+  }
+}
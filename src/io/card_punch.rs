@@ -1,7 +1,7 @@
 use std::fs::{File, OpenOptions};
 use std::io::{Seek, SeekFrom, Write};
 
-use super::io_device::IoDevice;
+use super::io_device::{IoDevice, IoMode};
 use super::{ActualDevice, SlimComputer};
 
 use crate::mix;
@@ -13,7 +13,7 @@ pub struct CardPunch {
 }
 
 impl CardPunch {
-  pub fn new(filename: &str) -> IoDevice {
+  pub fn new(filename: &str, mode: IoMode) -> IoDevice {
     let file = OpenOptions::new()
       .write(true)
       .create(true)
@@ -21,7 +21,7 @@ impl CardPunch {
       .unwrap();
 
     let tape = CardPunch { file };
-    IoDevice::new(Box::new(tape))
+    IoDevice::new(Box::new(tape), mode)
   }
 
   const fn block_size() -> usize {
@@ -35,19 +35,24 @@ impl CardPunch {
 }
 
 impl ActualDevice for CardPunch {
-  fn read(&mut self, _computer: &SlimComputer) -> Vec<mix::Word> {
-    panic!("cannot read from a card punch");
+  fn read(&mut self, _computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+    Err(String::from("cannot read from a card punch"))
   }
 
-  fn write(&mut self, words: &[mix::Word], computer: &SlimComputer) {
-    panic!("OUT for card punch not implemented");
+  fn write(&mut self, words: &[mix::Word], computer: &SlimComputer) -> Result<(), String> {
+    Err(String::from("OUT for card punch not implemented"))
   }
 
-  fn control(&mut self, _m: isize, computer: &SlimComputer) {
-    panic!("IOC for card punch not implemented")
+  fn control(&mut self, _m: isize, computer: &SlimComputer) -> Result<(), String> {
+    Err(String::from("IOC for card punch not implemented"))
   }
 
   fn block_size(&self) -> usize {
     CardPunch::block_size()
   }
+
+  // 1000 cards a minute
+  fn transfer_time(&self) -> usize {
+    60000
+  }
 }
@@ -1,22 +1,30 @@
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader};
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Seek, SeekFrom};
 
-use super::io_device::IoDevice;
+use super::io_device::{IoDevice, IoMode};
 use super::{ActualDevice, SlimComputer};
 
 use crate::mix;
 
 pub struct CardReader {
-  reader: BufReader<File>,
+  filename: String,
+  // a missing deck only matters once something tries to read it
+  reader: Option<BufReader<File>>,
 }
 
 impl CardReader {
-  pub fn new(filename: &str) -> IoDevice {
-    let file = OpenOptions::new().read(true).open(filename).unwrap();
-    let reader = BufReader::new(file);
+  pub fn new(filename: &str, mode: IoMode) -> IoDevice {
+    let reader = match File::open(filename) {
+      Ok(file) => Some(BufReader::new(file)),
+      Err(e) if e.kind() == ErrorKind::NotFound => None,
+      Err(e) => panic!("{}: {}", filename, e),
+    };
 
-    let tape = CardReader { reader };
-    IoDevice::new(Box::new(tape))
+    let tape = CardReader {
+      filename: String::from(filename),
+      reader,
+    };
+    IoDevice::new(Box::new(tape), mode)
   }
 
   const fn block_size() -> usize {
@@ -25,38 +33,126 @@ impl CardReader {
 }
 
 impl ActualDevice for CardReader {
-  fn read(&mut self, _computer: &SlimComputer) -> Vec<mix::Word> {
+  fn read(&mut self, _computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+    let reader = match self.reader.as_mut() {
+      Some(reader) => reader,
+      None => return Err(format!("no deck in the card reader ({})", self.filename)),
+    };
+
     let mut line = String::new();
-    self.reader.read_line(&mut line).unwrap();
-    line.pop();
+    let read = reader.read_line(&mut line).map_err(|e| e.to_string())?;
+    if read == 0 {
+      return Err(format!("no more cards in {}", self.filename));
+    }
+    if line.ends_with('\n') {
+      line.pop();
+    }
     eprintln!("READ {:?}", line);
 
-    let mut remaining = &line[..];
+    Ok(words_from_line(&line, self.block_size()))
+  }
+
+  fn write(&mut self, _words: &[mix::Word], _computer: &SlimComputer) -> Result<(), String> {
+    Err(String::from("cannot write to a card reader"))
+  }
+
+  fn control(&mut self, _m: isize, _computer: &SlimComputer) -> Result<(), String> {
+    Err(String::from("no IOC for card reader"))
+  }
+
+  fn block_size(&self) -> usize {
+    CardReader::block_size()
+  }
+
+  // 1000 cards a minute
+  fn transfer_time(&self) -> usize {
+    60000
+  }
+
+  fn position(&mut self) -> Result<u64, String> {
+    match self.reader.as_mut() {
+      Some(reader) => reader.stream_position().map_err(|e| e.to_string()),
+      None => Ok(0),
+    }
+  }
 
-    let mut words = vec![];
-    let mut new_s;
-    for _ in 0..self.block_size() {
-      if remaining.len() < 5 {
-        new_s = format!("{:5}", remaining);
-        remaining = &new_s;
-      }
-      let (left, right) = remaining.split_at(5);
-      remaining = right;
-      words.push(mix::Word::from_char_code(left));
+  fn set_position(&mut self, position: u64) -> Result<(), String> {
+    if let Some(reader) = self.reader.as_mut() {
+      reader
+        .seek(SeekFrom::Start(position))
+        .map_err(|e| e.to_string())?;
     }
 
-    words
+    Ok(())
   }
+}
 
-  fn write(&mut self, _words: &[mix::Word], _computer: &SlimComputer) {
-    panic!("cannot write to a card reader");
+/// Splits a card into `count` words of five characters, padding it with
+/// spaces.
+pub(super) fn words_from_line(line: &str, count: usize) -> Vec<mix::Word> {
+  let mut remaining = line;
+
+  let mut words = vec![];
+  let mut new_s;
+  for _ in 0..count {
+    if remaining.len() < 5 {
+      new_s = format!("{:5}", remaining);
+      remaining = &new_s;
+    }
+    let (left, right) = remaining.split_at(5);
+    remaining = right;
+    words.push(mix::Word::from_char_code(left));
   }
 
-  fn control(&mut self, _m: isize, _computer: &SlimComputer) {
-    panic!("no IOC for card reader")
+  words
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::computer::{ComputerBuilder, MixFault};
+  use crate::io::IoMode;
+  use crate::mix;
+
+  fn read_cards(deck: &str, cards: usize) -> Result<(), MixFault> {
+    let mut computer = ComputerBuilder::new()
+      .io_mode(IoMode::Synchronous)
+      .io_directory(std::env::temp_dir().join("negroni-card-reader-test"))
+      .device_file(16, deck)
+      .build()
+      .unwrap();
+
+    let instruction = mix::Instruction {
+      address: mix::Address::from_value(1000),
+      index_specification: 0,
+      modification: 16,
+      operation: mix::op_codes::IN,
+    };
+    for _ in 0..cards {
+      instruction.decode().unwrap().execute(&mut computer)?;
+      computer.wait_for_devices()?;
+    }
+
+    Ok(())
   }
 
-  fn block_size(&self) -> usize {
-    CardReader::block_size()
+  #[test]
+  fn test_end_of_deck() {
+    let directory = std::env::temp_dir().join("negroni-card-reader-test");
+    std::fs::create_dir_all(&directory).unwrap();
+    let deck = directory.join("deck.txt");
+    std::fs::write(&deck, "ONE CARD\n").unwrap();
+    let deck = deck.to_str().unwrap();
+
+    assert_eq!(read_cards(deck, 1), Ok(()));
+    match read_cards(deck, 2) {
+      Err(MixFault::Device { message, .. }) => assert!(message.starts_with("no more cards")),
+      result => panic!("expected a device fault, got {:?}", result),
+    }
+
+    let missing = directory.join("missing.txt");
+    match read_cards(missing.to_str().unwrap(), 1) {
+      Err(MixFault::Device { message, .. }) => assert!(message.starts_with("no deck")),
+      result => panic!("expected a device fault, got {:?}", result),
+    }
   }
 }
@@ -0,0 +1,187 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use super::{StandardInput, StandardOutput, Unattached};
+
+use crate::computer::ComputerBuilder;
+
+/// What a unit is attached to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attachment {
+  File(PathBuf),
+  /// stdin for the card reader, or stdout for the card punch and printer
+  Standard,
+  Unattached,
+}
+
+impl Attachment {
+  /// `-` means stdin or stdout, `none` leaves the unit unattached, and
+  /// anything else is a path.
+  pub fn parse(text: &str) -> Attachment {
+    match text {
+      "-" => Attachment::Standard,
+      "none" => Attachment::Unattached,
+      _ => Attachment::File(PathBuf::from(text)),
+    }
+  }
+}
+
+/// Which files the units use. Units that aren't mentioned get the usual
+/// file, such as `tape3.dat`, in `directory`. In TOML, that's a
+/// `directory = "jobs/42"` and a `[units]` table with entries such as
+/// `16 = "deck.txt"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceConfig {
+  pub directory: Option<PathBuf>,
+  pub units: BTreeMap<usize, Attachment>,
+}
+
+#[derive(Deserialize)]
+struct ConfigFile {
+  directory: Option<PathBuf>,
+  #[serde(default)]
+  units: HashMap<String, String>,
+}
+
+impl DeviceConfig {
+  pub fn load(path: &str) -> Result<DeviceConfig, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    DeviceConfig::parse(&text).map_err(|e| format!("{}: {}", path, e))
+  }
+
+  pub fn parse(text: &str) -> Result<DeviceConfig, String> {
+    let file: ConfigFile = toml::from_str(text).map_err(|e| e.to_string())?;
+
+    let mut config = DeviceConfig {
+      directory: file.directory,
+      units: BTreeMap::new(),
+    };
+    for (unit, attachment) in file.units.iter() {
+      config
+        .units
+        .insert(parse_unit(unit)?, Attachment::parse(attachment));
+    }
+
+    Ok(config)
+  }
+
+  /// Adds a mapping such as `16=deck.txt`, replacing any there was for that
+  /// unit.
+  pub fn add_mapping(&mut self, mapping: &str) -> Result<(), String> {
+    let mut parts = mapping.splitn(2, '=');
+    let unit = parts.next().unwrap_or("");
+    let attachment = parts
+      .next()
+      .ok_or_else(|| format!("expected UNIT=PATH, got {}", mapping))?;
+
+    self
+      .units
+      .insert(parse_unit(unit)?, Attachment::parse(attachment));
+    Ok(())
+  }
+
+  /// Attaches the devices to the computer that `builder` will build.
+  pub fn apply(&self, mut builder: ComputerBuilder) -> Result<ComputerBuilder, String> {
+    if let Some(directory) = &self.directory {
+      builder = builder.io_directory(directory);
+    }
+
+    for (unit, attachment) in self.units.iter() {
+      builder = match attachment {
+        Attachment::File(path) => builder.device_file(*unit, path),
+        Attachment::Unattached => builder.device(*unit, Box::new(Unattached)),
+        Attachment::Standard => match unit {
+          16 => builder.device(*unit, Box::new(StandardInput)),
+          17 => builder.device(*unit, Box::new(StandardOutput::card_punch())),
+          18 => builder.device(*unit, Box::new(StandardOutput::line_printer())),
+          _ => return Err(format!("unit {} can't use stdin or stdout", unit)),
+        },
+      };
+    }
+
+    Ok(builder)
+  }
+}
+
+fn parse_unit(text: &str) -> Result<usize, String> {
+  text
+    .trim()
+    .parse()
+    .map_err(|_| format!("invalid unit: {}", text))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::io::IoMode;
+  use crate::mix;
+
+  #[test]
+  fn test_device_config() {
+    let mut config = DeviceConfig::parse(
+      "\
+directory = \"jobs/42\"
+
+[units]
+16 = \"deck.txt\"
+18 = \"-\"
+0 = \"none\"
+",
+    )
+    .unwrap();
+    config.add_mapping("18=printer.txt").unwrap();
+    config.add_mapping("3=-").unwrap();
+
+    assert_eq!(config.directory, Some(PathBuf::from("jobs/42")));
+    let units: Vec<(&usize, &Attachment)> = config.units.iter().collect();
+    assert_eq!(
+      units,
+      vec![
+        (&0, &Attachment::Unattached),
+        (&3, &Attachment::Standard),
+        (&16, &Attachment::File(PathBuf::from("deck.txt"))),
+        (&18, &Attachment::File(PathBuf::from("printer.txt"))),
+      ]
+    );
+
+    // tapes can't be stdin or stdout
+    assert!(config.apply(ComputerBuilder::new()).is_err());
+
+    assert!(config.add_mapping("18").is_err());
+    assert!(config.add_mapping("X=tape.dat").is_err());
+    assert!(DeviceConfig::parse("[units]\nX = \"-\"").is_err());
+  }
+
+  #[test]
+  fn test_unattached() {
+    let directory = std::env::temp_dir().join("negroni-config-test");
+    let mut config = DeviceConfig {
+      directory: Some(directory.clone()),
+      units: BTreeMap::new(),
+    };
+    config.add_mapping("5=none").unwrap();
+    config
+      .add_mapping(&format!("6={}", directory.join("six.dat").display()))
+      .unwrap();
+
+    let builder = ComputerBuilder::new().io_mode(IoMode::Synchronous);
+    let mut computer = config.apply(builder).unwrap().build().unwrap();
+    assert!(directory.join("six.dat").exists());
+    assert!(!directory.join("tape6.dat").exists());
+
+    let instruction = mix::Instruction {
+      address: mix::Address::from_value(1000),
+      index_specification: 0,
+      modification: 5,
+      operation: mix::op_codes::IN,
+    };
+    computer.memory[0].write(mix::Word::from_instruction(&instruction));
+    computer.fetch_decode_execute().unwrap();
+    let fault = computer.wait_for_devices().unwrap_err();
+    assert!(fault.to_string().starts_with("unit 5: nothing is attached"));
+  }
+}
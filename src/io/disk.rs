@@ -1,7 +1,7 @@
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom};
 
-use super::io_device::IoDevice;
+use super::io_device::{IoDevice, IoMode};
 use super::{ActualDevice, SlimComputer};
 
 use crate::mix;
@@ -13,7 +13,7 @@ pub struct DiskUnit {
 }
 
 impl DiskUnit {
-  pub fn new(filename: &str) -> IoDevice {
+  pub fn new(filename: &str, mode: IoMode) -> IoDevice {
     let file = OpenOptions::new()
       .read(true)
       .write(true)
@@ -22,7 +22,7 @@ impl DiskUnit {
       .unwrap();
 
     let tape = DiskUnit { file };
-    IoDevice::new(Box::new(tape))
+    IoDevice::new(Box::new(tape), mode)
   }
 
   const fn block_size() -> usize {
@@ -33,43 +33,70 @@ impl DiskUnit {
     let words = [mix::Word::zero(); DiskUnit::block_size()];
     bincode::serialized_size(&words[..]).unwrap() as usize
   }
-}
 
-impl ActualDevice for DiskUnit {
-  fn read(&mut self, computer: &SlimComputer) -> Vec<mix::Word> {
-    let block = computer.extension.read().value();
+  fn seek_block(&mut self, computer: &SlimComputer) -> Result<(), String> {
+    let block = computer.extension()?.value_in(computer.byte_size());
+    if block < 0 {
+      return Err(format!("invalid disk block {}", block));
+    }
+
     self
       .file
       .seek(SeekFrom::Start(block as u64 * DiskUnit::word_size() as u64))
-      .unwrap();
+      .map_err(|e| e.to_string())?;
+
+    Ok(())
+  }
+}
+
+impl ActualDevice for DiskUnit {
+  fn read(&mut self, computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+    self.seek_block(computer)?;
 
     let mut buffer = vec![0; DiskUnit::word_size()];
-    self.file.read(&mut buffer).unwrap();
-    bincode::deserialize(&buffer).unwrap()
+    self.file.read(&mut buffer).map_err(|e| e.to_string())?;
+    bincode::deserialize(&buffer).map_err(|e| e.to_string())
   }
 
-  fn write(&mut self, words: &[mix::Word], computer: &SlimComputer) {
-    let block = computer.extension.read().value();
-    self
-      .file
-      .seek(SeekFrom::Start(block as u64 * DiskUnit::word_size() as u64))
-      .unwrap();
+  fn write(&mut self, words: &[mix::Word], computer: &SlimComputer) -> Result<(), String> {
+    self.seek_block(computer)?;
 
-    bincode::serialize_into(&self.file, words).unwrap();
+    bincode::serialize_into(&self.file, words).map_err(|e| e.to_string())?;
     eprintln!("done write");
     eprintln!("new pos: {}", self.file.seek(SeekFrom::Current(0)).unwrap());
+
+    Ok(())
   }
 
-  fn control(&mut self, _m: isize, computer: &SlimComputer) {
+  fn control(&mut self, _m: isize, computer: &SlimComputer) -> Result<(), String> {
     // TODO: does this actually save time later?
-    let block = computer.extension.read().value();
-    self
-      .file
-      .seek(SeekFrom::Start(block as u64 * DiskUnit::word_size() as u64))
-      .unwrap();
+    self.seek_block(computer)
   }
 
   fn block_size(&self) -> usize {
     DiskUnit::block_size()
   }
+
+  // a transfer from the current track is quick, but moving the arm with IOC
+  // isn't
+  fn transfer_time(&self) -> usize {
+    1000
+  }
+
+  fn seek_time(&self) -> usize {
+    10000
+  }
+
+  fn position(&mut self) -> Result<u64, String> {
+    self.file.stream_position().map_err(|e| e.to_string())
+  }
+
+  fn set_position(&mut self, position: u64) -> Result<(), String> {
+    self
+      .file
+      .seek(SeekFrom::Start(position))
+      .map_err(|e| e.to_string())?;
+
+    Ok(())
+  }
 }
@@ -1,114 +1,365 @@
+use std::ops::Range;
 use std::sync::mpsc;
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
+use std::time::Instant;
 
 use super::{ActualDevice, IoMessage, SlimComputer};
 
-use crate::computer;
+use crate::computer::{self, Access, MixFault};
 use crate::mix;
 
+/// How devices carry out transfers.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum IoMode {
+  /// Each unit has a thread of its own, and runs alongside the computer
+  /// like real hardware. Whether JBUS and JRED see a unit as busy depends on
+  /// the host's scheduler.
+  #[default]
+  Threaded,
+  /// Transfers finish before the instruction that started them does, but
+  /// the unit stays busy for its transfer or seek time by the machine's
+  /// clock, so JBUS and JRED see the same thing on every run. No threads
+  /// are started.
+  Synchronous,
+}
+
 pub struct IoDevice {
   pub busy_pair: Arc<(Mutex<bool>, Condvar)>,
-  pub channel: mpsc::Sender<IoMessage>,
-  pub set_computer: mpsc::Sender<SlimComputer>,
+  pub fault: Arc<Mutex<Option<MixFault>>>,
+  pub interrupt: Arc<Mutex<bool>>,
+  pub position: Arc<Mutex<u64>>,
   pub block_size: usize,
+  pub transfer_time: usize,
+  pub seek_time: usize,
+  // the clock time when the device will have finished what it's doing
+  ready_at: Mutex<usize>,
+  // the operation and memory of the last IN or OUT
+  buffer: Mutex<Option<(u8, Range<isize>)>>,
+  backend: Backend,
+}
+
+enum Backend {
+  Threaded {
+    channel: mpsc::Sender<IoMessage>,
+    set_computer: mpsc::Sender<SlimComputer>,
+  },
+  Synchronous(Mutex<Synchronous>),
 }
 
-pub struct InternalDevice<'a> {
+struct Synchronous {
+  actual_device: Box<dyn ActualDevice + Send>,
+  computer: Option<SlimComputer>,
+}
+
+struct InternalDevice {
   busy_pair: Arc<(Mutex<bool>, Condvar)>,
-  rx: &'a mpsc::Receiver<IoMessage>,
+  fault: Arc<Mutex<Option<MixFault>>>,
+  interrupt: Arc<Mutex<bool>>,
+  position: Arc<Mutex<u64>>,
 }
 
-impl<'a> InternalDevice<'a> {
+impl InternalDevice {
   fn set_ready(&self) {
     let (lock, cvar) = &*self.busy_pair;
     let mut b = lock.lock().unwrap();
     *b = false;
     cvar.notify_all();
   }
+
+  fn set_fault(&self, fault: MixFault) {
+    let mut f = self.fault.lock().unwrap();
+    *f = Some(fault);
+  }
+
+  fn set_interrupt(&self) {
+    let mut i = self.interrupt.lock().unwrap();
+    *i = true;
+  }
+
+  fn complete(
+    &self,
+    actual_device: &mut (dyn ActualDevice + Send),
+    computer: &SlimComputer,
+    message: &IoMessage,
+  ) {
+    let result = transfer(actual_device, computer, message, &self.position);
+    if let Err(fault) = result {
+      self.set_fault(fault);
+    }
+
+    // the interrupt must be visible by the time the device is ready
+    self.set_interrupt();
+    self.set_ready();
+  }
 }
 
 impl IoDevice {
-  pub fn new(mut actual_device: Box<dyn ActualDevice + Send>) -> IoDevice {
-    let (tx, rx) = mpsc::channel::<IoMessage>();
-    let (start_tx, start_rx) = mpsc::channel::<SlimComputer>();
+  pub fn new(mut actual_device: Box<dyn ActualDevice + Send>, mode: IoMode) -> IoDevice {
     let busy_pair = Arc::new((Mutex::new(false), Condvar::new()));
-    let internal_busy_pair = busy_pair.clone();
+    let fault = Arc::new(Mutex::new(None));
+    let interrupt = Arc::new(Mutex::new(false));
+    let position = Arc::new(Mutex::new(0));
 
     let bs = actual_device.block_size();
+    let transfer_time = actual_device.transfer_time();
+    let seek_time = actual_device.seek_time();
 
-    thread::spawn(move || {
-      let td = InternalDevice {
-        busy_pair: internal_busy_pair,
-        rx: &rx,
-      };
-
-      let computer = &start_rx.recv().unwrap();
+    let backend = match mode {
+      IoMode::Threaded => {
+        let (tx, rx) = mpsc::channel::<IoMessage>();
+        let (start_tx, start_rx) = mpsc::channel::<SlimComputer>();
+        let td = InternalDevice {
+          busy_pair: busy_pair.clone(),
+          fault: fault.clone(),
+          interrupt: interrupt.clone(),
+          position: position.clone(),
+        };
+        thread::spawn(move || {
+          let computer = &start_rx.recv().unwrap();
 
-      for received in td.rx {
-        match received.operation {
-          mix::op_codes::IN => {
-            let words = actual_device.read(computer);
-            for (index, word) in words.iter().enumerate() {
-              computer.memory[index + received.address as usize].write(*word);
-            }
+          for received in rx {
+            td.complete(actual_device.as_mut(), computer, &received);
           }
-          mix::op_codes::OUT => {
-            let words: Vec<mix::Word> = (0..actual_device.block_size())
-              .map(|index| computer.memory[index + received.address as usize].read())
-              .collect();
-            actual_device.write(&words, computer);
-          }
-          mix::op_codes::IOC => {
-            actual_device.control(received.address, computer);
-          }
-          _ => panic!("unknown IO operation {}", received.operation),
-        }
+        });
 
-        td.set_ready();
+        Backend::Threaded {
+          channel: tx,
+          set_computer: start_tx,
+        }
       }
-    });
+      IoMode::Synchronous => Backend::Synchronous(Mutex::new(Synchronous {
+        actual_device,
+        computer: None,
+      })),
+    };
 
     IoDevice {
-      channel: tx,
-      set_computer: start_tx,
       busy_pair,
+      fault,
+      interrupt,
+      position,
       block_size: bs,
+      transfer_time,
+      seek_time,
+      ready_at: Mutex::new(0),
+      buffer: Mutex::new(None),
+      backend,
     }
   }
 
   pub fn start(&self, computer: &computer::Computer) {
     let memory = computer.memory.clone();
     let extension = computer.extension.clone();
-    self
-      .set_computer
-      .send(SlimComputer { memory, extension })
-      .unwrap();
+    let watches = computer.watches.clone();
+    let slim = SlimComputer {
+      byte_size: computer.byte_size,
+      memory,
+      extension,
+      watches,
+    };
+
+    match &self.backend {
+      Backend::Threaded { set_computer, .. } => set_computer.send(slim).unwrap(),
+      Backend::Synchronous(synchronous) => synchronous.lock().unwrap().computer = Some(slim),
+    }
+  }
+
+  /// Whether the device is still busy when the machine's clock is at
+  /// `clock`, either with the transfer itself or for the time it takes.
+  pub fn busy(&self, clock: usize) -> bool {
+    clock < self.ready_at() || self.transferring()
   }
 
-  pub fn busy(&self) -> bool {
+  fn transferring(&self) -> bool {
     let (lock, _) = &*self.busy_pair;
     let busy = lock.lock().unwrap();
     *busy
   }
 
-  pub fn set_busy(&self) {
+  /// Marks the device as busy with `operation` on the memory at `address`,
+  /// starting at `clock`.
+  pub fn set_busy(&self, operation: u8, address: isize, clock: usize) {
     let (lock, _) = &*self.busy_pair;
     let mut busy = lock.lock().unwrap();
     *busy = true;
+
+    let time = match operation {
+      mix::op_codes::IOC => self.seek_time,
+      _ => {
+        let buffer = address..address + self.block_size as isize;
+        *self.buffer.lock().unwrap() = Some((operation, buffer));
+        self.transfer_time
+      }
+    };
+    *self.ready_at.lock().unwrap() = clock + time;
+  }
+
+  /// The memory that the last IN or OUT used, and which of the two it was.
+  /// It's only in use while the device is busy.
+  pub fn buffer(&self) -> Option<(u8, Range<isize>)> {
+    self.buffer.lock().unwrap().clone()
+  }
+
+  pub fn ready_at(&self) -> usize {
+    *self.ready_at.lock().unwrap()
   }
 
   pub fn wait_ready(&self) {
+    self.wait_ready_until(None);
+  }
+
+  /// Waits for the device to finish, but not past `deadline`. Returns
+  /// whether it finished.
+  pub fn wait_ready_until(&self, deadline: Option<Instant>) -> bool {
     let (lock, cvar) = &*self.busy_pair;
     let mut busy = lock.lock().unwrap();
     while *busy {
-      busy = cvar.wait(busy).unwrap();
+      busy = match deadline {
+        Some(deadline) => {
+          let now = Instant::now();
+          if now >= deadline {
+            return false;
+          }
+          cvar.wait_timeout(busy, deadline - now).unwrap().0
+        }
+        None => cvar.wait(busy).unwrap(),
+      };
+    }
+
+    true
+  }
+
+  pub fn check_fault(&self) -> Result<(), MixFault> {
+    let mut fault = self.fault.lock().unwrap();
+    match fault.take() {
+      Some(fault) => Err(fault),
+      None => Ok(()),
     }
   }
 
+  pub fn take_interrupt(&self) -> bool {
+    let mut interrupt = self.interrupt.lock().unwrap();
+    let result = *interrupt;
+    *interrupt = false;
+    result
+  }
+
+  pub fn position(&self) -> u64 {
+    *self.position.lock().unwrap()
+  }
+
+  pub fn set_position(&self, position: u64) {
+    let mut p = self.position.lock().unwrap();
+    *p = position;
+  }
+
+  /// Starts a transfer. A synchronous device has finished it by the time
+  /// this returns.
   pub fn send(&self, message: IoMessage) -> Result<(), mpsc::SendError<IoMessage>> {
-    self.channel.send(message)?;
+    match &self.backend {
+      Backend::Threaded { channel, .. } => channel.send(message)?,
+      Backend::Synchronous(synchronous) => {
+        let mut synchronous = synchronous.lock().unwrap();
+        let Synchronous {
+          actual_device,
+          computer,
+        } = &mut *synchronous;
+        let computer = match computer {
+          Some(computer) => computer,
+          None => return Err(mpsc::SendError(message)),
+        };
+
+        let td = InternalDevice {
+          busy_pair: self.busy_pair.clone(),
+          fault: self.fault.clone(),
+          interrupt: self.interrupt.clone(),
+          position: self.position.clone(),
+        };
+        td.complete(actual_device.as_mut(), computer, &message);
+      }
+    }
 
     Ok(())
   }
-}
\ No newline at end of file
+}
+
+fn transfer(
+  actual_device: &mut (dyn ActualDevice + Send),
+  computer: &SlimComputer,
+  message: &IoMessage,
+  position: &Mutex<u64>,
+) -> Result<(), MixFault> {
+  let device_fault = |message_text: String| MixFault::Device {
+    program_counter: message.program_counter,
+    instruction: message.instruction,
+    message: message_text,
+  };
+  let locked_fault = |address: usize| MixFault::MemoryLocked {
+    program_counter: message.program_counter,
+    instruction: message.instruction,
+    address: address as isize,
+  };
+  let report = |address: usize, access: Access| {
+    computer.watches.report(
+      address as isize,
+      access,
+      message.program_counter,
+      Some(message.instruction.modification),
+    )
+  };
+
+  // the position may have been changed by restoring a snapshot
+  let start = *position.lock().unwrap();
+  actual_device.set_position(start).map_err(device_fault)?;
+
+  match message.operation {
+    mix::op_codes::IN => {
+      let words = actual_device.read(computer).map_err(device_fault)?;
+      if words.len() != actual_device.block_size() {
+        return Err(device_fault(format!(
+          "read {} words instead of {}",
+          words.len(),
+          actual_device.block_size()
+        )));
+      }
+      for (index, word) in words.iter().enumerate() {
+        let address = index + message.address as usize;
+        computer.memory[address]
+          .try_write(*word)
+          .ok_or_else(|| locked_fault(address))?;
+        report(address, Access::Write);
+      }
+    }
+    mix::op_codes::OUT => {
+      let mut words = Vec::with_capacity(actual_device.block_size());
+      for index in 0..actual_device.block_size() {
+        let address = index + message.address as usize;
+        let word = computer.memory[address]
+          .try_read()
+          .ok_or_else(|| locked_fault(address))?;
+        report(address, Access::Read);
+        words.push(word);
+      }
+      actual_device
+        .write(&words, computer)
+        .map_err(device_fault)?;
+    }
+    mix::op_codes::IOC => {
+      actual_device
+        .control(message.address, computer)
+        .map_err(device_fault)?;
+    }
+    _ => {
+      return Err(device_fault(format!(
+        "unknown IO operation {}",
+        message.operation
+      )))
+    }
+  }
+
+  let end = actual_device.position().map_err(device_fault)?;
+  *position.lock().unwrap() = end;
+
+  Ok(())
+}
@@ -1,7 +1,7 @@
 use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::io::{Seek, SeekFrom, Write};
 
-use super::io_device::IoDevice;
+use super::io_device::{IoDevice, IoMode};
 use super::{ActualDevice, SlimComputer};
 
 use crate::mix;
@@ -13,7 +13,7 @@ pub struct LinePrinter {
 }
 
 impl LinePrinter {
-  pub fn new(filename: &str) -> IoDevice {
+  pub fn new(filename: &str, mode: IoMode) -> IoDevice {
     let file = OpenOptions::new()
       .write(true)
       .create(true)
@@ -21,7 +21,7 @@ impl LinePrinter {
       .unwrap();
 
     let tape = LinePrinter { file };
-    IoDevice::new(Box::new(tape))
+    IoDevice::new(Box::new(tape), mode)
   }
 
   const fn block_size() -> usize {
@@ -35,11 +35,11 @@ impl LinePrinter {
 }
 
 impl ActualDevice for LinePrinter {
-  fn read(&mut self, _computer: &SlimComputer) -> Vec<mix::Word> {
-    panic!("cannot read from a line printer");
+  fn read(&mut self, _computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+    Err(String::from("cannot read from a line printer"))
   }
 
-  fn write(&mut self, words: &[mix::Word], _computer: &SlimComputer) {
+  fn write(&mut self, words: &[mix::Word], _computer: &SlimComputer) -> Result<(), String> {
     let mut line = String::new();
     for word in words.iter() {
       let word_str = word.to_char_code();
@@ -47,14 +47,42 @@ impl ActualDevice for LinePrinter {
     }
     line.push('\n');
 
-    self.file.write(line.as_bytes()).unwrap();
+    self
+      .file
+      .write(line.as_bytes())
+      .map_err(|e| e.to_string())?;
+
+    Ok(())
   }
 
-  fn control(&mut self, _m: isize, _computer: &SlimComputer) {
+  fn control(&mut self, _m: isize, _computer: &SlimComputer) -> Result<(), String> {
     // panic!("IOC for line printer not implemented")
+    Ok(())
   }
 
   fn block_size(&self) -> usize {
     LinePrinter::block_size()
   }
+
+  // 1200 lines a minute, and about as long again to skip to a new page
+  fn transfer_time(&self) -> usize {
+    50000
+  }
+
+  fn seek_time(&self) -> usize {
+    50000
+  }
+
+  fn position(&mut self) -> Result<u64, String> {
+    self.file.stream_position().map_err(|e| e.to_string())
+  }
+
+  fn set_position(&mut self, position: u64) -> Result<(), String> {
+    self
+      .file
+      .seek(SeekFrom::Start(position))
+      .map_err(|e| e.to_string())?;
+
+    Ok(())
+  }
 }
@@ -5,13 +5,19 @@ mod card_punch;
 mod disk;
 mod tape;
 mod line_printer;
+mod standard;
+mod unattached;
+mod config;
 
-pub use io_device::IoDevice;
+pub use io_device::{IoDevice, IoMode};
 pub use card_reader::CardReader;
 pub use card_punch::CardPunch;
 pub use disk::DiskUnit;
 pub use tape::TapeUnit;
 pub use line_printer::LinePrinter;
+pub use standard::{StandardInput, StandardOutput};
+pub use unattached::Unattached;
+pub use config::{Attachment, DeviceConfig};
 
 use crate::computer;
 use crate::mix;
@@ -20,16 +26,151 @@ use std::sync::Arc;
 pub struct IoMessage {
   pub operation: u8,
   pub address: isize,
+  pub program_counter: isize,
+  pub instruction: mix::Instruction,
 }
 
+/// What a device can see of the computer while it carries out an
+/// operation.
 pub struct SlimComputer {
+  byte_size: isize,
   memory: Arc<Vec<computer::MemoryCell>>,
   extension: Arc<computer::MemoryCell>,
+  watches: Arc<computer::Watches>,
 }
 
+impl SlimComputer {
+  /// The machine's byte size, for turning words into numbers.
+  pub fn byte_size(&self) -> isize {
+    self.byte_size
+  }
+
+  /// The contents of rX, which a disk uses as the block to seek to. It's
+  /// an error if an instruction is using rX at the same time.
+  pub fn extension(&self) -> Result<mix::Word, String> {
+    self
+      .extension
+      .try_read()
+      .ok_or_else(|| String::from("rX is in use"))
+  }
+}
+
+/// A unit, such as a tape or a card reader. Implement this to make a
+/// device of your own, and attach it with `ComputerBuilder::device`.
+///
+/// The computer moves the words between memory and the device, so a device
+/// only sees the block being transferred. Errors fault the instruction that
+/// started the operation.
 pub trait ActualDevice {
-  fn read(&mut self, computer: &SlimComputer) -> Vec<mix::Word>;
-  fn write(&mut self, bytes: &[mix::Word], computer: &SlimComputer);
-  fn control(&mut self, m: isize, computer: &SlimComputer);
+  /// Reads a block for IN, which has to be `block_size` words long.
+  fn read(&mut self, computer: &SlimComputer) -> Result<Vec<mix::Word>, String>;
+  /// Writes a block for OUT.
+  fn write(&mut self, bytes: &[mix::Word], computer: &SlimComputer) -> Result<(), String>;
+  /// Carries out an IOC, with its indexed address as `m`.
+  fn control(&mut self, m: isize, computer: &SlimComputer) -> Result<(), String>;
   fn block_size(&self) -> usize;
+
+  /// How long an IN or OUT keeps the device busy, in units of machine time.
+  fn transfer_time(&self) -> usize {
+    0
+  }
+
+  /// How long an IOC keeps the device busy, such as to rewind a tape.
+  fn seek_time(&self) -> usize {
+    0
+  }
+
+  /// Where the device is up to, e.g. the offset into a tape, so that it can
+  /// be saved in a snapshot.
+  fn position(&mut self) -> Result<u64, String> {
+    Ok(0)
+  }
+
+  fn set_position(&mut self, _position: u64) -> Result<(), String> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::sync::Mutex;
+
+  use crate::computer::ComputerBuilder;
+
+  // answers IN with rX and the last IOC, and keeps what OUT wrote
+  struct Recorder {
+    control: isize,
+    written: Arc<Mutex<Vec<mix::Word>>>,
+  }
+
+  impl ActualDevice for Recorder {
+    fn read(&mut self, computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+      Ok(vec![
+        computer.extension()?,
+        mix::Word::from_value(self.control),
+      ])
+    }
+
+    fn write(&mut self, words: &[mix::Word], _computer: &SlimComputer) -> Result<(), String> {
+      self.written.lock().unwrap().extend_from_slice(words);
+      Ok(())
+    }
+
+    fn control(&mut self, m: isize, _computer: &SlimComputer) -> Result<(), String> {
+      self.control = m;
+      Ok(())
+    }
+
+    fn block_size(&self) -> usize {
+      2
+    }
+  }
+
+  #[test]
+  fn test_custom_device() {
+    let written = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Recorder {
+      control: 0,
+      written: written.clone(),
+    };
+    let mut computer = ComputerBuilder::new()
+      .io_mode(IoMode::Synchronous)
+      .io_directory(std::env::temp_dir().join("negroni-device-test"))
+      .device(20, Box::new(recorder))
+      .extension(mix::Word::from_value(42))
+      .build()
+      .unwrap();
+
+    computer.memory[1000].write(mix::Word::from_value(1));
+    computer.memory[1001].write(mix::Word::from_value(2));
+
+    let instructions = [
+      (mix::op_codes::IOC, 7),
+      (mix::op_codes::OUT, 1000),
+      (mix::op_codes::IN, 2000),
+    ];
+    for (operation, address) in instructions.iter() {
+      let instruction = mix::Instruction {
+        address: mix::Address::from_value(*address),
+        index_specification: 0,
+        modification: 20,
+        operation: *operation,
+      };
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
+    }
+    computer.wait_for_devices().unwrap();
+
+    assert_eq!(
+      *written.lock().unwrap(),
+      vec![mix::Word::from_value(1), mix::Word::from_value(2)]
+    );
+    assert_eq!(computer.memory[2000].read(), mix::Word::from_value(42));
+    assert_eq!(computer.memory[2001].read(), mix::Word::from_value(7));
+  }
 }
@@ -0,0 +1,95 @@
+use std::io::{self, BufRead, Write};
+
+use super::card_reader;
+use super::{ActualDevice, SlimComputer};
+
+use crate::mix;
+
+/// A card reader that reads its deck from stdin, one card per line.
+pub struct StandardInput;
+
+impl ActualDevice for StandardInput {
+  fn read(&mut self, _computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+    let mut line = String::new();
+    let stdin = io::stdin();
+    let read = stdin
+      .lock()
+      .read_line(&mut line)
+      .map_err(|e| e.to_string())?;
+    if read == 0 {
+      return Err(String::from("no more cards on stdin"));
+    }
+    if line.ends_with('\n') {
+      line.pop();
+    }
+
+    Ok(card_reader::words_from_line(&line, self.block_size()))
+  }
+
+  fn write(&mut self, _words: &[mix::Word], _computer: &SlimComputer) -> Result<(), String> {
+    Err(String::from("cannot write to a card reader"))
+  }
+
+  fn control(&mut self, _m: isize, _computer: &SlimComputer) -> Result<(), String> {
+    Err(String::from("no IOC for card reader"))
+  }
+
+  fn block_size(&self) -> usize {
+    16
+  }
+
+  fn transfer_time(&self) -> usize {
+    60000
+  }
+}
+
+/// A card punch or line printer that writes each block to stdout as a line
+/// of characters.
+pub struct StandardOutput {
+  block_size: usize,
+  transfer_time: usize,
+}
+
+impl StandardOutput {
+  pub fn card_punch() -> StandardOutput {
+    StandardOutput {
+      block_size: 16,
+      transfer_time: 60000,
+    }
+  }
+
+  pub fn line_printer() -> StandardOutput {
+    StandardOutput {
+      block_size: 24,
+      transfer_time: 50000,
+    }
+  }
+}
+
+impl ActualDevice for StandardOutput {
+  fn read(&mut self, _computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+    Err(String::from("cannot read from stdout"))
+  }
+
+  fn write(&mut self, words: &[mix::Word], _computer: &SlimComputer) -> Result<(), String> {
+    let line: String = words.iter().map(|word| word.to_char_code()).collect();
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    writeln!(stdout, "{}", line)
+      .and_then(|_| stdout.flush())
+      .map_err(|e| e.to_string())
+  }
+
+  fn control(&mut self, _m: isize, _computer: &SlimComputer) -> Result<(), String> {
+    Ok(())
+  }
+
+  fn block_size(&self) -> usize {
+    self.block_size
+  }
+
+  fn transfer_time(&self) -> usize {
+    self.transfer_time
+  }
+}
@@ -1,7 +1,7 @@
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom};
 
-use super::io_device::IoDevice;
+use super::io_device::{IoDevice, IoMode};
 use super::{ActualDevice, SlimComputer};
 
 use crate::mix;
@@ -13,7 +13,7 @@ pub struct TapeUnit {
 }
 
 impl TapeUnit {
-  pub fn new(filename: &str) -> IoDevice {
+  pub fn new(filename: &str, mode: IoMode) -> IoDevice {
     let file = OpenOptions::new()
       .read(true)
       .write(true)
@@ -22,7 +22,7 @@ impl TapeUnit {
       .unwrap();
 
     let tape = TapeUnit { file };
-    IoDevice::new(Box::new(tape))
+    IoDevice::new(Box::new(tape), mode)
   }
 
   const fn block_size() -> usize {
@@ -36,29 +36,59 @@ impl TapeUnit {
 }
 
 impl ActualDevice for TapeUnit {
-  fn read(&mut self, _computer: &SlimComputer) -> Vec<mix::Word> {
+  fn read(&mut self, _computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
     let mut buffer = vec![0; TapeUnit::word_size()];
-    self.file.read(&mut buffer).unwrap();
-    bincode::deserialize(&buffer).unwrap()
+    self.file.read(&mut buffer).map_err(|e| e.to_string())?;
+    bincode::deserialize(&buffer).map_err(|e| e.to_string())
   }
 
-  fn write(&mut self, words: &[mix::Word], _computer: &SlimComputer) {
-    bincode::serialize_into(&self.file, words).unwrap();
+  fn write(&mut self, words: &[mix::Word], _computer: &SlimComputer) -> Result<(), String> {
+    bincode::serialize_into(&self.file, words).map_err(|e| e.to_string())?;
     eprintln!("done write");
     eprintln!("new pos: {}", self.file.seek(SeekFrom::Current(0)).unwrap());
+
+    Ok(())
   }
 
-  fn control(&mut self, m: isize, _computer: &SlimComputer) {
+  fn control(&mut self, m: isize, _computer: &SlimComputer) -> Result<(), String> {
     eprintln!(
       "current pos: {}",
       self.file.seek(SeekFrom::Current(0)).unwrap()
     );
     let to_seek = m * TapeUnit::word_size() as isize;
     eprintln!("moving {} blocks, that's {} bytes", m, to_seek);
-    self.file.seek(SeekFrom::Current(to_seek as i64)).unwrap();
+    self
+      .file
+      .seek(SeekFrom::Current(to_seek as i64))
+      .map_err(|e| e.to_string())?;
+
+    Ok(())
   }
 
   fn block_size(&self) -> usize {
     TapeUnit::block_size()
   }
+
+  // about 5ms to move a block, and 10ms to rewind or skip, taking a unit of
+  // time as a microsecond
+  fn transfer_time(&self) -> usize {
+    5000
+  }
+
+  fn seek_time(&self) -> usize {
+    10000
+  }
+
+  fn position(&mut self) -> Result<u64, String> {
+    self.file.stream_position().map_err(|e| e.to_string())
+  }
+
+  fn set_position(&mut self, position: u64) -> Result<(), String> {
+    self
+      .file
+      .seek(SeekFrom::Start(position))
+      .map_err(|e| e.to_string())?;
+
+    Ok(())
+  }
 }
@@ -0,0 +1,24 @@
+use super::{ActualDevice, SlimComputer};
+
+use crate::mix;
+
+/// A unit with nothing attached, where every operation faults.
+pub struct Unattached;
+
+impl ActualDevice for Unattached {
+  fn read(&mut self, _computer: &SlimComputer) -> Result<Vec<mix::Word>, String> {
+    Err(String::from("nothing is attached"))
+  }
+
+  fn write(&mut self, _words: &[mix::Word], _computer: &SlimComputer) -> Result<(), String> {
+    Err(String::from("nothing is attached"))
+  }
+
+  fn control(&mut self, _m: isize, _computer: &SlimComputer) -> Result<(), String> {
+    Err(String::from("nothing is attached"))
+  }
+
+  fn block_size(&self) -> usize {
+    0
+  }
+}
@@ -1,4 +1,5 @@
 pub mod computer;
+pub mod debugger;
 
 pub mod io;
 pub mod mix;
@@ -1,6 +1,8 @@
-use super::{Sign, Word};
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+use super::{Sign, Word, BINARY_BYTE_SIZE};
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Address {
   pub sign: Sign,
   pub bytes: [u8; 2],
@@ -21,7 +23,8 @@ impl Address {
     }
   }
 
-  pub fn from_value(value: isize) -> Address {
+  /// The address for `value` on a MIX with bytes of `byte_size`.
+  pub fn from_value_in(value: isize, byte_size: isize) -> Address {
     let sign = if value.signum() < 0 {
       Sign::Negative
     } else {
@@ -30,13 +33,13 @@ impl Address {
     let value = value.abs();
 
     Address {
-      bytes: [(value / 64) as u8, (value % 64) as u8],
+      bytes: [(value / byte_size) as u8, (value % byte_size) as u8],
       sign,
     }
   }
 
-  pub fn value(&self) -> isize {
-    let magnitude = (self.bytes[0] as isize) * 64 + (self.bytes[1] as isize);
+  pub fn value_in(&self, byte_size: isize) -> isize {
+    let magnitude = (self.bytes[0] as isize) * byte_size + (self.bytes[1] as isize);
 
     let sign = if self.sign == Sign::Positive { 1 } else { -1 };
     magnitude * sign
@@ -48,4 +51,14 @@ impl Address {
       sign: self.sign,
     }
   }
+
+  // the same for a binary MIX
+
+  pub fn from_value(value: isize) -> Address {
+    Address::from_value_in(value, BINARY_BYTE_SIZE)
+  }
+
+  pub fn value(&self) -> isize {
+    self.value_in(BINARY_BYTE_SIZE)
+  }
 }
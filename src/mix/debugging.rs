@@ -2,13 +2,15 @@ use std::fmt;
 
 use super::{Address, Sign, Word};
 
+// without a byte size there's no value, so these only show the bytes
+
 impl fmt::Debug for Word {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     write!(
       f,
-      "{} [{}]",
-      fmt_signed_bytes(&self.sign, &self.bytes, self.value()),
-      fmt_instruction(self)
+      "{} {}",
+      fmt_sign(&self.sign),
+      fmt_bytes(&self.bytes).trim_end()
     )
   }
 }
@@ -17,19 +19,40 @@ impl fmt::Debug for Address {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     write!(
       f,
-      "{}",
-      fmt_signed_bytes(&self.sign, &self.bytes, self.value())
+      "{} {}",
+      fmt_sign(&self.sign),
+      fmt_bytes(&self.bytes).trim_end()
     )
   }
 }
 
+impl Word {
+  /// The bytes and value of the word on a MIX with bytes of `byte_size`,
+  /// along with how it reads as an instruction.
+  pub fn describe(&self, byte_size: isize) -> String {
+    format!(
+      "{} [{}]",
+      fmt_signed_bytes(&self.sign, &self.bytes, self.value_in(byte_size)),
+      fmt_instruction(self, byte_size)
+    )
+  }
+}
+
+impl Address {
+  /// The bytes and value of the address on a MIX with bytes of
+  /// `byte_size`.
+  pub fn describe(&self, byte_size: isize) -> String {
+    fmt_signed_bytes(&self.sign, &self.bytes, self.value_in(byte_size))
+  }
+}
+
 fn fmt_signed_bytes(sign: &Sign, bytes: &[u8], value: isize) -> String {
   format!("{} {} ({:010})", fmt_sign(sign), fmt_bytes(bytes), value)
 }
 
-fn fmt_instruction(word: &Word) -> String {
+fn fmt_instruction(word: &Word, byte_size: isize) -> String {
   let address = &word.bytes[..2];
-  let address_val = address[0] as isize * 64 + address[1] as isize;
+  let address_val = address[0] as isize * byte_size + address[1] as isize;
   format!(
     "{} {:04} {}",
     fmt_sign(&word.sign),
@@ -51,4 +74,4 @@ fn fmt_sign(sign: &Sign) -> &'static str {
     Sign::Positive => "+",
     Sign::Negative => "-",
   }
-}
\ No newline at end of file
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use super::op_codes::{self, OP_CODES};
+use super::{decode_field_spec, Instruction, Sign, Word};
+
+lazy_static! {
+  // the other way round from OP_CODES, by operation and default field
+  static ref NAMES: HashMap<(u8, u8), &'static str> = OP_CODES
+    .iter()
+    .map(|(name, op_code)| ((op_code.value, op_code.default_fs), *name))
+    .collect();
+}
+
+/// Names for addresses, so that we can show `LDA BUF+3` rather than
+/// `LDA 2003`.
+pub trait Labels {
+  fn label(&self, address: isize) -> Option<String>;
+}
+
+/// Symbols from the assembler, where an address is named after the closest
+/// symbol at or below it. Ties go to the name that sorts first.
+impl Labels for HashMap<String, isize> {
+  fn label(&self, address: isize) -> Option<String> {
+    let (name, value) = self
+      .iter()
+      .filter(|(_, value)| **value <= address)
+      .max_by(|(a, x), (b, y)| x.cmp(y).then(b.cmp(a)))?;
+
+    if *value == address {
+      Some(name.clone())
+    } else {
+      Some(format!("{}+{}", name, address - value))
+    }
+  }
+}
+
+/// Turns `word` back into MIXAL, such as `LDA 2000,1(1:3)`, or returns None
+/// if it isn't a valid instruction. Default fields are left out, and an
+/// address equal to `location` is written as `*`. Addresses and constants
+/// are read with bytes of `byte_size`.
+pub fn disassemble(word: Word, location: Option<isize>, byte_size: isize) -> Option<String> {
+  disassemble_with_labels(word, location, None, byte_size)
+}
+
+/// The same as `disassemble`, but addresses that refer to memory are named
+/// by `labels`.
+pub fn disassemble_with_labels(
+  word: Word,
+  location: Option<isize>,
+  labels: Option<&dyn Labels>,
+  byte_size: isize,
+) -> Option<String> {
+  let instruction = Instruction::from_word(word);
+  if instruction.index_specification > 6 {
+    return None;
+  }
+
+  let (name, field) = name_and_field(&instruction)?;
+
+  let address = instruction.address.value_in(byte_size);
+  let mut operand = match (instruction.address.sign, location) {
+    (Sign::Negative, _) if address == 0 => String::from("-0"),
+    (_, Some(location)) if address == location => String::from("*"),
+    _ => match labels {
+      Some(labels) if refers_to_memory(instruction.operation) => {
+        labels.label(address).unwrap_or_else(|| address.to_string())
+      }
+      _ => address.to_string(),
+    },
+  };
+  if instruction.index_specification != 0 {
+    operand.push_str(&format!(",{}", instruction.index_specification));
+  }
+  if let Some(field) = field {
+    operand.push_str(&format!("({})", field));
+  }
+
+  if operand == "0" {
+    Some(String::from(name))
+  } else {
+    Some(format!("{} {}", name, operand))
+  }
+}
+
+/// The same as `disassemble`, but words that aren't instructions come out
+/// as a `CON`.
+pub fn to_mixal(word: Word, location: Option<isize>, byte_size: isize) -> String {
+  to_mixal_with_labels(word, location, None, byte_size)
+}
+
+pub fn to_mixal_with_labels(
+  word: Word,
+  location: Option<isize>,
+  labels: Option<&dyn Labels>,
+  byte_size: isize,
+) -> String {
+  match disassemble_with_labels(word, location, labels, byte_size) {
+    Some(text) => text,
+    None if word.sign == Sign::Negative && word.value_in(byte_size) == 0 => String::from("CON -0"),
+    None => format!("CON {}", word.value_in(byte_size)),
+  }
+}
+
+fn name_and_field(instruction: &Instruction) -> Option<(&'static str, Option<String>)> {
+  let operation = instruction.operation;
+  let field = instruction.modification;
+
+  if let Some(name) = NAMES.get(&(operation, field)) {
+    return Some((name, None));
+  }
+
+  let mut names = OP_CODES
+    .iter()
+    .filter(|(_, op_code)| op_code.value == operation);
+
+  if takes_field_spec(operation) {
+    if !is_field_spec(field) {
+      return None;
+    }
+
+    // FADD and friends share an operation with ADD, but not a field spec
+    let (name, _) = names.find(|(_, op_code)| is_field_spec(op_code.default_fs))?;
+    let (left, right) = decode_field_spec(field);
+    Some((name, Some(format!("{}:{}", left, right))))
+  } else {
+    // otherwise the field only means something else when it doesn't pick
+    // between instructions, such as the unit for IN or OUT
+    let (name, _) = names.next()?;
+    if names.next().is_some() {
+      return None;
+    }
+    Some((name, Some(field.to_string())))
+  }
+}
+
+fn takes_field_spec(operation: u8) -> bool {
+  matches!(
+    operation,
+    op_codes::ADD..=op_codes::DIV | op_codes::LDA..=op_codes::STZ | op_codes::CMPA..=op_codes::CMPX
+  )
+}
+
+// rather than being a number, such as a shift amount or the value for ENTA
+fn refers_to_memory(operation: u8) -> bool {
+  !matches!(
+    operation,
+    op_codes::NOP | op_codes::HLT | op_codes::SLA | op_codes::IOC | op_codes::ENTA..=op_codes::ENTX
+  )
+}
+
+fn is_field_spec(field: u8) -> bool {
+  let (left, right) = decode_field_spec(field);
+  left <= right && right <= 5
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::mix::{field_spec, Address, BINARY_BYTE_SIZE};
+
+  fn word(address: isize, index: u8, field: u8, operation: u8) -> Word {
+    Word::from_instruction(&Instruction {
+      address: Address::from_value(address),
+      index_specification: index,
+      modification: field,
+      operation,
+    })
+  }
+
+  #[test]
+  fn test_disassemble() {
+    let tests = [
+      (
+        word(2000, 1, field_spec(1, 3), op_codes::LDA),
+        None,
+        Some("LDA 2000,1(1:3)"),
+      ),
+      (word(2000, 0, 5, op_codes::LDA), None, Some("LDA 2000")),
+      (
+        word(1000, 0, 16, op_codes::JBUS),
+        Some(1000),
+        Some("JBUS *(16)"),
+      ),
+      (word(-5, 2, 2, op_codes::ENTA), None, Some("ENTA -5,2")),
+      (word(0, 0, 2, op_codes::HLT), None, Some("HLT")),
+      (word(3000, 0, 6, op_codes::FADD), None, Some("FADD 3000")),
+      (word(3000, 0, 4, op_codes::ADD), None, Some("ADD 3000(0:4)")),
+      (word(100, 0, 0, op_codes::STJ), None, Some("STJ 100(0:0)")),
+      (word(100, 0, 2, op_codes::STJ), None, Some("STJ 100")),
+      (word(0, 0, 16, op_codes::IOC), None, Some("IOC 0(16)")),
+      (word(2, 0, 2, op_codes::SLAX), None, Some("SLAX 2")),
+      (word(1000, 0, 6, op_codes::JMP), None, Some("JG 1000")),
+      (word(0, 7, 5, op_codes::LDA), None, None),
+      (word(0, 0, field_spec(3, 1), op_codes::LDA), None, None),
+      (word(0, 0, 3, op_codes::HLT), None, None),
+      (word(0, 0, 10, op_codes::JMP), None, None),
+      (word(0, 0, 0, 70), None, None),
+    ];
+
+    for (word, location, expected) in tests.iter() {
+      assert_eq!(
+        disassemble(*word, *location, BINARY_BYTE_SIZE).as_deref(),
+        *expected,
+        "{:?}",
+        word
+      );
+    }
+
+    let data = Word {
+      bytes: [1, 2, 63, 4, 5],
+      sign: Sign::Negative,
+    };
+    assert_eq!(to_mixal(data, None, BINARY_BYTE_SIZE), "CON -17559813");
+  }
+
+  #[test]
+  fn test_labels() {
+    let mut symbols = HashMap::new();
+    symbols.insert(String::from("BUF"), 2000);
+    symbols.insert(String::from("START"), 1000);
+    symbols.insert(String::from("BEGIN"), 1000);
+    let labels: Option<&dyn Labels> = Some(&symbols);
+
+    let tests = [
+      (word(2003, 1, 5, op_codes::LDA), "LDA BUF+3,1"),
+      (word(1000, 0, 0, op_codes::JMP), "JMP BEGIN"),
+      (word(2000, 0, 2, op_codes::ENTA), "ENTA 2000"),
+      (word(999, 0, 0, op_codes::JMP), "JMP 999"),
+    ];
+
+    for (word, expected) in tests.iter() {
+      assert_eq!(
+        to_mixal_with_labels(*word, Some(1500), labels, BINARY_BYTE_SIZE),
+        *expected
+      );
+    }
+  }
+
+  #[test]
+  fn test_every_op_code() {
+    for (name, op_code) in OP_CODES.iter() {
+      let word = word(1000, 0, op_code.default_fs, op_code.value);
+      assert_eq!(
+        disassemble(word, None, BINARY_BYTE_SIZE),
+        Some(format!("{} 1000", name))
+      );
+    }
+  }
+}
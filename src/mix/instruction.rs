@@ -3,7 +3,7 @@ use super::{Address, Word};
 use crate::mix::op_codes;
 use crate::operations;
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Instruction {
   pub operation: u8,
   pub modification: u8,
@@ -25,22 +25,40 @@ impl Instruction {
     }
   }
 
-  pub fn decode(&self) -> Box<dyn operations::Operation + '_> {
-    match self.operation {
+  pub fn decode(&self) -> Option<Box<dyn operations::Operation + '_>> {
+    let operation: Box<dyn operations::Operation + '_> = match self.operation {
       op_codes::NOP => Box::new(operations::NoOp::new()),
       op_codes::HLT => match self.modification {
-        0 => Box::new(operations::ConvertToNumeric::new()),
-        1 => Box::new(operations::ConvertToCharacters::new()),
+        0 => Box::new(operations::ConvertToNumeric::new(self)),
+        1 => Box::new(operations::ConvertToCharacters::new(self)),
         2 => Box::new(operations::Halt::new(self)),
-        _ => panic!("unknown modification for special op: {}", self.modification),
+        6 => Box::new(operations::ConvertToFloatingPoint::new()),
+        7 => Box::new(operations::ConvertToFixedPoint::new()),
+        9 => Box::new(operations::Interrupt::new(self)),
+        _ => return None,
       },
       op_codes::SLA => Box::new(operations::Shift::new(self)),
       op_codes::MOVE => Box::new(operations::Move::new(self)),
-      op_codes::ADD | op_codes::SUB => Box::new(operations::Addition::new(self)),
-      op_codes::MUL => Box::new(operations::Multiplication::new(self)),
-      op_codes::DIV => Box::new(operations::Division::new(self)),
-      op_codes::LDA...op_codes::LDXN => Box::new(operations::Load::new(self)),
-      op_codes::STA...op_codes::STZ => Box::new(operations::Store::new(self)),
+      op_codes::FADD | op_codes::FSUB if self.modification == 6 => {
+        Box::new(operations::FloatingAddition::new(self))
+      }
+      op_codes::FMUL if self.modification == 6 => {
+        Box::new(operations::FloatingMultiplication::new(self))
+      }
+      op_codes::FDIV if self.modification == 6 => Box::new(operations::FloatingDivision::new(self)),
+      op_codes::ADD | op_codes::SUB if self.has_valid_field_spec() => {
+        Box::new(operations::Addition::new(self))
+      }
+      op_codes::MUL if self.has_valid_field_spec() => {
+        Box::new(operations::Multiplication::new(self))
+      }
+      op_codes::DIV if self.has_valid_field_spec() => Box::new(operations::Division::new(self)),
+      op_codes::LDA...op_codes::LDXN if self.has_valid_field_spec() => {
+        Box::new(operations::Load::new(self))
+      }
+      op_codes::STA...op_codes::STZ if self.has_valid_field_spec() => {
+        Box::new(operations::Store::new(self))
+      }
       op_codes::IOC...op_codes::OUT => Box::new(operations::Io::new(self)),
       op_codes::JMP => Box::new(operations::Jump::new(self)),
       op_codes::JAN...op_codes::JXN => Box::new(operations::RegisterJump::new(self)),
@@ -48,14 +66,21 @@ impl Instruction {
       op_codes::ENTA...op_codes::ENTX => match self.modification {
         0 | 1 => Box::new(operations::Increase::new(self)),
         2 | 3 => Box::new(operations::Enter::new(self)),
-        _ => panic!(
-          "unknown modification for address transfer: {}",
-          self.modification
-        ),
+        _ => return None,
       },
-      op_codes::CMPA...op_codes::CMPX => Box::new(operations::Compare::new(self)),
+      op_codes::FCMP if self.modification == 6 => Box::new(operations::FloatingCompare::new(self)),
+      op_codes::CMPA...op_codes::CMPX if self.has_valid_field_spec() => {
+        Box::new(operations::Compare::new(self))
+      }
 
-      _ => panic!("unknown opcode {}", self.operation),
-    }
+      _ => return None,
+    };
+
+    Some(operation)
+  }
+
+  fn has_valid_field_spec(&self) -> bool {
+    let (left, right) = super::decode_field_spec(self.modification);
+    left <= right && right <= 5
   }
 }
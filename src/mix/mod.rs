@@ -1,7 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 mod address;
 mod instruction;
 mod word;
 mod debugging;
+pub mod disasm;
 
 #[allow(dead_code)]
 pub mod op_codes;
@@ -10,13 +13,27 @@ pub use address::Address;
 pub use instruction::Instruction;
 pub use word::{Sign, Word};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Comparison {
   Less,
   Equal,
   Greater,
 }
 
+/// The byte size of a binary MIX, which is what a machine has unless it's
+/// set up with another one.
+pub const BINARY_BYTE_SIZE: isize = 64;
+
+/// Checks that `size` can be a byte size. Binary MIX has 64 and decimal
+/// MIX has 100, but anything in between is allowed (pg. 125).
+pub fn check_byte_size(size: isize) -> Result<(), &'static str> {
+  if !(64..=100).contains(&size) {
+    return Err("byte size must be between 64 and 100");
+  }
+
+  Ok(())
+}
+
 pub fn field_spec(left: u8, right: u8) -> u8 {
   left * 8 + right
 }
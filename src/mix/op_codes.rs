@@ -1,11 +1,20 @@
+use std::collections::HashMap;
+
 pub const NOP: u8 = 0;
 pub const ADD: u8 = 1;
 pub const SUB: u8 = 2;
 pub const MUL: u8 = 3;
 pub const DIV: u8 = 4;
+pub const FADD: u8 = 1;
+pub const FSUB: u8 = 2;
+pub const FMUL: u8 = 3;
+pub const FDIV: u8 = 4;
 pub const NUM: u8 = 5;
 pub const CHAR: u8 = 5;
 pub const HLT: u8 = 5;
+pub const FLOT: u8 = 5;
+pub const FIX: u8 = 5;
+pub const INT: u8 = 5;
 
 pub const SLA: u8 = 6;
 pub const SRA: u8 = 6;
@@ -13,6 +22,8 @@ pub const SLAX: u8 = 6;
 pub const SRAX: u8 = 6;
 pub const SLC: u8 = 6;
 pub const SRC: u8 = 6;
+pub const SLB: u8 = 6;
+pub const SRB: u8 = 6;
 
 pub const MOVE: u8 = 7;
 
@@ -66,48 +77,64 @@ pub const JAP: u8 = 40;
 pub const JANN: u8 = 40;
 pub const JANZ: u8 = 40;
 pub const JANP: u8 = 40;
+pub const JAE: u8 = 40;
+pub const JAO: u8 = 40;
 pub const J1N: u8 = 41;
 pub const J1Z: u8 = 41;
 pub const J1P: u8 = 41;
 pub const J1NN: u8 = 41;
 pub const J1NZ: u8 = 41;
 pub const J1NP: u8 = 41;
+pub const J1E: u8 = 41;
+pub const J1O: u8 = 41;
 pub const J2N: u8 = 42;
 pub const J2Z: u8 = 42;
 pub const J2P: u8 = 42;
 pub const J2NN: u8 = 42;
 pub const J2NZ: u8 = 42;
 pub const J2NP: u8 = 42;
+pub const J2E: u8 = 42;
+pub const J2O: u8 = 42;
 pub const J3N: u8 = 43;
 pub const J3Z: u8 = 43;
 pub const J3P: u8 = 43;
 pub const J3NN: u8 = 43;
 pub const J3NZ: u8 = 43;
 pub const J3NP: u8 = 43;
+pub const J3E: u8 = 43;
+pub const J3O: u8 = 43;
 pub const J4N: u8 = 44;
 pub const J4Z: u8 = 44;
 pub const J4P: u8 = 44;
 pub const J4NN: u8 = 44;
 pub const J4NZ: u8 = 44;
 pub const J4NP: u8 = 44;
+pub const J4E: u8 = 44;
+pub const J4O: u8 = 44;
 pub const J5N: u8 = 45;
 pub const J5Z: u8 = 45;
 pub const J5P: u8 = 45;
 pub const J5NN: u8 = 45;
 pub const J5NZ: u8 = 45;
 pub const J5NP: u8 = 45;
+pub const J5E: u8 = 45;
+pub const J5O: u8 = 45;
 pub const J6N: u8 = 46;
 pub const J6Z: u8 = 46;
 pub const J6P: u8 = 46;
 pub const J6NN: u8 = 46;
 pub const J6NZ: u8 = 46;
 pub const J6NP: u8 = 46;
+pub const J6E: u8 = 46;
+pub const J6O: u8 = 46;
 pub const JXN: u8 = 47;
 pub const JXZ: u8 = 47;
 pub const JXP: u8 = 47;
 pub const JXNN: u8 = 47;
 pub const JXNZ: u8 = 47;
 pub const JXNP: u8 = 47;
+pub const JXE: u8 = 47;
+pub const JXO: u8 = 47;
 
 pub const INCA: u8 = 48;
 pub const DECA: u8 = 48;
@@ -143,6 +170,7 @@ pub const ENTX: u8 = 55;
 pub const ENNX: u8 = 55;
 
 pub const CMPA: u8 = 56;
+pub const FCMP: u8 = 56;
 pub const CMP1: u8 = 57;
 pub const CMP2: u8 = 58;
 pub const CMP3: u8 = 59;
@@ -150,3 +178,187 @@ pub const CMP4: u8 = 60;
 pub const CMP5: u8 = 61;
 pub const CMP6: u8 = 62;
 pub const CMPX: u8 = 63;
+
+// the MIXAL name of every operation, with its default field
+pub struct OpCode {
+  pub value: u8,
+  pub default_fs: u8,
+}
+
+#[rustfmt::skip]
+lazy_static! {
+  pub static ref OP_CODES: HashMap<&'static str, OpCode> = {
+    let mut m = HashMap::new();
+    m.insert("NOP", OpCode {value: NOP, default_fs: 0});
+    m.insert("ADD", OpCode  {value: ADD, default_fs: 5});
+    m.insert("SUB", OpCode  {value: SUB, default_fs: 5});
+    m.insert("MUL", OpCode  {value: MUL, default_fs: 5});
+    m.insert("DIV", OpCode  {value: DIV, default_fs: 5});
+    m.insert("FADD", OpCode {value: FADD, default_fs: 6});
+    m.insert("FSUB", OpCode {value: FSUB, default_fs: 6});
+    m.insert("FMUL", OpCode {value: FMUL, default_fs: 6});
+    m.insert("FDIV", OpCode {value: FDIV, default_fs: 6});
+    m.insert("NUM", OpCode  {value: NUM, default_fs: 0});
+    m.insert("CHAR", OpCode {value: CHAR, default_fs: 1});
+    m.insert("HLT", OpCode  {value: HLT, default_fs: 2});
+    m.insert("FLOT", OpCode {value: FLOT, default_fs: 6});
+    m.insert("FIX", OpCode  {value: FIX, default_fs: 7});
+    m.insert("INT", OpCode  {value: INT, default_fs: 9});
+    m.insert("SLA", OpCode  {value: SLA, default_fs: 0});
+    m.insert("SRA", OpCode  {value: SRA, default_fs: 1});
+    m.insert("SLAX", OpCode {value: SLAX, default_fs: 2});
+    m.insert("SRAX", OpCode {value: SRAX, default_fs: 3});
+    m.insert("SLC", OpCode  {value: SLC, default_fs: 4});
+    m.insert("SRC", OpCode  {value: SRC, default_fs: 5});
+    m.insert("SLB", OpCode  {value: SLB, default_fs: 6});
+    m.insert("SRB", OpCode  {value: SRB, default_fs: 7});
+    m.insert("MOVE", OpCode {value: MOVE, default_fs: 1});
+    m.insert("LDA", OpCode  {value: LDA, default_fs: 5});
+    m.insert("LD1", OpCode  {value: LD1, default_fs: 5});
+    m.insert("LD2", OpCode  {value: LD2, default_fs: 5});
+    m.insert("LD3", OpCode  {value: LD3, default_fs: 5});
+    m.insert("LD4", OpCode  {value: LD4, default_fs: 5});
+    m.insert("LD5", OpCode  {value: LD5, default_fs: 5});
+    m.insert("LD6", OpCode  {value: LD6, default_fs: 5});
+    m.insert("LDX", OpCode  {value: LDX, default_fs: 5});
+    m.insert("LDAN", OpCode {value: LDAN, default_fs: 5});
+    m.insert("LD1N", OpCode {value: LD1N, default_fs: 5});
+    m.insert("LD2N", OpCode {value: LD2N, default_fs: 5});
+    m.insert("LD3N", OpCode {value: LD3N, default_fs: 5});
+    m.insert("LD4N", OpCode {value: LD4N, default_fs: 5});
+    m.insert("LD5N", OpCode {value: LD5N, default_fs: 5});
+    m.insert("LD6N", OpCode {value: LD6N, default_fs: 5});
+    m.insert("LDXN", OpCode {value: LDXN, default_fs: 5});
+    m.insert("STA", OpCode  {value: STA, default_fs: 5});
+    m.insert("ST1", OpCode  {value: ST1, default_fs: 5});
+    m.insert("ST2", OpCode  {value: ST2, default_fs: 5});
+    m.insert("ST3", OpCode  {value: ST3, default_fs: 5});
+    m.insert("ST4", OpCode  {value: ST4, default_fs: 5});
+    m.insert("ST5", OpCode  {value: ST5, default_fs: 5});
+    m.insert("ST6", OpCode  {value: ST6, default_fs: 5});
+    m.insert("STX", OpCode  {value: STX, default_fs: 5});
+    m.insert("STJ", OpCode  {value: STJ, default_fs: 2});
+    m.insert("STZ", OpCode  {value: STZ, default_fs: 5});
+    m.insert("JBUS", OpCode {value: JBUS, default_fs: 0});
+    m.insert("IOC", OpCode  {value: IOC, default_fs: 0});
+    m.insert("IN", OpCode   {value: IN, default_fs: 0});
+    m.insert("OUT", OpCode  {value: OUT, default_fs: 0});
+    m.insert("JRED", OpCode {value: JRED, default_fs: 0});
+    m.insert("JMP", OpCode  {value: JMP, default_fs: 0});
+    m.insert("JSJ", OpCode  {value: JSJ, default_fs: 1});
+    m.insert("JOV", OpCode  {value: JOV, default_fs: 2});
+    m.insert("JNOV", OpCode {value: JNOV, default_fs: 3});
+    m.insert("JL", OpCode   {value: JL, default_fs: 4});
+    m.insert("JE", OpCode   {value: JE, default_fs: 5});
+    m.insert("JG", OpCode   {value: JG, default_fs: 6});
+    m.insert("JGE", OpCode  {value: JGE, default_fs: 7});
+    m.insert("JNE", OpCode  {value: JNE, default_fs: 8});
+    m.insert("JLE", OpCode  {value: JLE, default_fs: 9});
+    m.insert("JAN", OpCode  {value: JAN, default_fs: 0});
+    m.insert("JAZ", OpCode  {value: JAZ, default_fs: 1});
+    m.insert("JAP", OpCode  {value: JAP, default_fs: 2});
+    m.insert("JANN", OpCode {value: JANN, default_fs: 3});
+    m.insert("JANZ", OpCode {value: JANZ, default_fs: 4});
+    m.insert("JANP", OpCode {value: JANP, default_fs: 5});
+    m.insert("JAE", OpCode  {value: JAE, default_fs: 6});
+    m.insert("JAO", OpCode  {value: JAO, default_fs: 7});
+    m.insert("J1N", OpCode  {value: J1N, default_fs: 0});
+    m.insert("J1Z", OpCode  {value: J1Z, default_fs: 1});
+    m.insert("J1P", OpCode  {value: J1P, default_fs: 2});
+    m.insert("J1NN", OpCode {value: J1NN, default_fs: 3});
+    m.insert("J1NZ", OpCode {value: J1NZ, default_fs: 4});
+    m.insert("J1NP", OpCode {value: J1NP, default_fs: 5});
+    m.insert("J1E", OpCode  {value: J1E, default_fs: 6});
+    m.insert("J1O", OpCode  {value: J1O, default_fs: 7});
+    m.insert("J2N", OpCode  {value: J2N, default_fs: 0});
+    m.insert("J2Z", OpCode  {value: J2Z, default_fs: 1});
+    m.insert("J2P", OpCode  {value: J2P, default_fs: 2});
+    m.insert("J2NN", OpCode {value: J2NN, default_fs: 3});
+    m.insert("J2NZ", OpCode {value: J2NZ, default_fs: 4});
+    m.insert("J2NP", OpCode {value: J2NP, default_fs: 5});
+    m.insert("J2E", OpCode  {value: J2E, default_fs: 6});
+    m.insert("J2O", OpCode  {value: J2O, default_fs: 7});
+    m.insert("J3N", OpCode  {value: J3N, default_fs: 0});
+    m.insert("J3Z", OpCode  {value: J3Z, default_fs: 1});
+    m.insert("J3P", OpCode  {value: J3P, default_fs: 2});
+    m.insert("J3NN", OpCode {value: J3NN, default_fs: 3});
+    m.insert("J3NZ", OpCode {value: J3NZ, default_fs: 4});
+    m.insert("J3NP", OpCode {value: J3NP, default_fs: 5});
+    m.insert("J3E", OpCode  {value: J3E, default_fs: 6});
+    m.insert("J3O", OpCode  {value: J3O, default_fs: 7});
+    m.insert("J4N", OpCode  {value: J4N, default_fs: 0});
+    m.insert("J4Z", OpCode  {value: J4Z, default_fs: 1});
+    m.insert("J4P", OpCode  {value: J4P, default_fs: 2});
+    m.insert("J4NN", OpCode {value: J4NN, default_fs: 3});
+    m.insert("J4NZ", OpCode {value: J4NZ, default_fs: 4});
+    m.insert("J4NP", OpCode {value: J4NP, default_fs: 5});
+    m.insert("J4E", OpCode  {value: J4E, default_fs: 6});
+    m.insert("J4O", OpCode  {value: J4O, default_fs: 7});
+    m.insert("J5N", OpCode  {value: J5N, default_fs: 0});
+    m.insert("J5Z", OpCode  {value: J5Z, default_fs: 1});
+    m.insert("J5P", OpCode  {value: J5P, default_fs: 2});
+    m.insert("J5NN", OpCode {value: J5NN, default_fs: 3});
+    m.insert("J5NZ", OpCode {value: J5NZ, default_fs: 4});
+    m.insert("J5NP", OpCode {value: J5NP, default_fs: 5});
+    m.insert("J5E", OpCode  {value: J5E, default_fs: 6});
+    m.insert("J5O", OpCode  {value: J5O, default_fs: 7});
+    m.insert("J6N", OpCode  {value: J6N, default_fs: 0});
+    m.insert("J6Z", OpCode  {value: J6Z, default_fs: 1});
+    m.insert("J6P", OpCode  {value: J6P, default_fs: 2});
+    m.insert("J6NN", OpCode {value: J6NN, default_fs: 3});
+    m.insert("J6NZ", OpCode {value: J6NZ, default_fs: 4});
+    m.insert("J6NP", OpCode {value: J6NP, default_fs: 5});
+    m.insert("J6E", OpCode  {value: J6E, default_fs: 6});
+    m.insert("J6O", OpCode  {value: J6O, default_fs: 7});
+    m.insert("JXN", OpCode  {value: JXN, default_fs: 0});
+    m.insert("JXZ", OpCode  {value: JXZ, default_fs: 1});
+    m.insert("JXP", OpCode  {value: JXP, default_fs: 2});
+    m.insert("JXNN", OpCode {value: JXNN, default_fs: 3});
+    m.insert("JXNZ", OpCode {value: JXNZ, default_fs: 4});
+    m.insert("JXNP", OpCode {value: JXNP, default_fs: 5});
+    m.insert("JXE", OpCode  {value: JXE, default_fs: 6});
+    m.insert("JXO", OpCode  {value: JXO, default_fs: 7});
+    m.insert("INCA", OpCode {value: INCA, default_fs: 0});
+    m.insert("DECA", OpCode {value: DECA, default_fs: 1});
+    m.insert("ENTA", OpCode {value: ENTA, default_fs: 2});
+    m.insert("ENNA", OpCode {value: ENNA, default_fs: 3});
+    m.insert("INC1", OpCode {value: INC1, default_fs: 0});
+    m.insert("DEC1", OpCode {value: DEC1, default_fs: 1});
+    m.insert("ENT1", OpCode {value: ENT1, default_fs: 2});
+    m.insert("ENN1", OpCode {value: ENN1, default_fs: 3});
+    m.insert("INC2", OpCode {value: INC2, default_fs: 0});
+    m.insert("DEC2", OpCode {value: DEC2, default_fs: 1});
+    m.insert("ENT2", OpCode {value: ENT2, default_fs: 2});
+    m.insert("ENN2", OpCode {value: ENN2, default_fs: 3});
+    m.insert("INC3", OpCode {value: INC3, default_fs: 0});
+    m.insert("DEC3", OpCode {value: DEC3, default_fs: 1});
+    m.insert("ENT3", OpCode {value: ENT3, default_fs: 2});
+    m.insert("ENN3", OpCode {value: ENN3, default_fs: 3});
+    m.insert("INC4", OpCode {value: INC4, default_fs: 0});
+    m.insert("DEC4", OpCode {value: DEC4, default_fs: 1});
+    m.insert("ENT4", OpCode {value: ENT4, default_fs: 2});
+    m.insert("ENN4", OpCode {value: ENN4, default_fs: 3});
+    m.insert("INC5", OpCode {value: INC5, default_fs: 0});
+    m.insert("DEC5", OpCode {value: DEC5, default_fs: 1});
+    m.insert("ENT5", OpCode {value: ENT5, default_fs: 2});
+    m.insert("ENN5", OpCode {value: ENN5, default_fs: 3});
+    m.insert("INC6", OpCode {value: INC6, default_fs: 0});
+    m.insert("DEC6", OpCode {value: DEC6, default_fs: 1});
+    m.insert("ENT6", OpCode {value: ENT6, default_fs: 2});
+    m.insert("ENN6", OpCode {value: ENN6, default_fs: 3});
+    m.insert("INCX", OpCode {value: INCX, default_fs: 0});
+    m.insert("DECX", OpCode {value: DECX, default_fs: 1});
+    m.insert("ENTX", OpCode {value: ENTX, default_fs: 2});
+    m.insert("ENNX", OpCode {value: ENNX, default_fs: 3});
+    m.insert("CMPA", OpCode {value: CMPA, default_fs: 5});
+    m.insert("CMP1", OpCode {value: CMP1, default_fs: 5});
+    m.insert("CMP2", OpCode {value: CMP2, default_fs: 5});
+    m.insert("CMP3", OpCode {value: CMP3, default_fs: 5});
+    m.insert("CMP4", OpCode {value: CMP4, default_fs: 5});
+    m.insert("CMP5", OpCode {value: CMP5, default_fs: 5});
+    m.insert("CMP6", OpCode {value: CMP6, default_fs: 5});
+    m.insert("CMPX", OpCode {value: CMPX, default_fs: 5});
+    m.insert("FCMP", OpCode {value: FCMP, default_fs: 6});
+    m
+  };
+}
@@ -1,4 +1,4 @@
-use super::{char_codes, Address, Instruction};
+use super::{char_codes, Address, Instruction, BINARY_BYTE_SIZE};
 
 use serde::{Deserialize, Serialize};
 
@@ -29,26 +29,29 @@ impl Word {
     }
   }
 
-  pub fn from_value(value: isize) -> Word {
-    if !Word::fits_in_word(value) {
+  /// The word for `value` on a MIX with bytes of `byte_size`, which has to
+  /// fit in five bytes.
+  pub fn from_value_in(value: isize, byte_size: isize) -> Word {
+    if !Word::fits_in(value, byte_size) {
       panic!("unexpected word overflow! value: {:?}", value);
     }
 
-    Word::from_value_impl(value, false)
+    Word::from_value_impl(value, byte_size, false)
   }
 
-  pub fn from_value_with_overflow(value: isize) -> Word {
-    if Word::fits_in_word(value) {
+  /// The low five bytes of `value`, which overflowed a word.
+  pub fn from_value_with_overflow_in(value: isize, byte_size: isize) -> Word {
+    if Word::fits_in(value, byte_size) {
       panic!(
         "from_value_with_overflow is only for word overflow! value: {:?}",
         value
       );
     }
 
-    Word::from_value_impl(value, true)
+    Word::from_value_impl(value, byte_size, true)
   }
 
-  fn from_value_impl(mut value: isize, allow_overflow: bool) -> Word {
+  fn from_value_impl(mut value: isize, byte_size: isize, allow_overflow: bool) -> Word {
     let mut word = Word::zero();
 
     if value < 0 {
@@ -57,11 +60,11 @@ impl Word {
     }
 
     if allow_overflow {
-      value = value - 64isize.pow(5);
+      value = value - byte_size.pow(5);
     }
 
     for i in 0..5 {
-      let max_val = 64isize.pow(4 - i);
+      let max_val = byte_size.pow(4 - i);
       let b = (value / max_val) as u8;
       word.bytes[i as usize] = b;
       value %= max_val
@@ -103,18 +106,19 @@ impl Word {
     result
   }
 
-  pub fn fits_in_word(value: isize) -> bool {
-    let max = 64isize.pow(5) - 1;
+  pub fn fits_in(value: isize, byte_size: isize) -> bool {
+    let max = byte_size.pow(5) - 1;
     value.abs() <= max
   }
 
-  pub fn value(&self) -> isize {
+  /// The value of the word on a MIX with bytes of `byte_size`.
+  pub fn value_in(&self, byte_size: isize) -> isize {
     let magnitude = self
       .bytes
       .iter()
       .enumerate()
       .fold(0, |total, (index, byte)| {
-        total + (64isize.pow(5 - index as u32 - 1) * *byte as isize)
+        total + (byte_size.pow(5 - index as u32 - 1) * *byte as isize)
       });
 
     let sign = if self.sign == Sign::Positive { 1 } else { -1 };
@@ -163,4 +167,22 @@ impl Word {
       sign: self.sign,
     }
   }
+
+  // the same for a binary MIX
+
+  pub fn from_value(value: isize) -> Word {
+    Word::from_value_in(value, BINARY_BYTE_SIZE)
+  }
+
+  pub fn from_value_with_overflow(value: isize) -> Word {
+    Word::from_value_with_overflow_in(value, BINARY_BYTE_SIZE)
+  }
+
+  pub fn fits_in_word(value: isize) -> bool {
+    Word::fits_in(value, BINARY_BYTE_SIZE)
+  }
+
+  pub fn value(&self) -> isize {
+    self.value_in(BINARY_BYTE_SIZE)
+  }
 }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::mix;
 
-use super::OP_CODES;
+use super::{DebugInfo, OP_CODES};
 
 use super::{
   AlfStatement, Asterisk, ConStatement, EndStatement, EquStatement, Expression, LiteralConstant,
@@ -13,6 +13,8 @@ use super::{
 pub struct Assembler {
   pub words: HashMap<usize, mix::Word>,
   pub program_start: usize,
+  pub lines: HashMap<usize, usize>,
+  byte_size: isize,
   location_counter: usize,
   symbol_table: HashMap<String, isize>,
   future_reference_table: HashMap<String, Vec<usize>>,
@@ -24,6 +26,8 @@ impl Assembler {
     Assembler {
       words: HashMap::new(),
       program_start: 0,
+      lines: HashMap::new(),
+      byte_size: mix::BINARY_BYTE_SIZE,
       location_counter: 0,
       symbol_table: HashMap::new(),
       future_reference_table: HashMap::new(),
@@ -31,6 +35,19 @@ impl Assembler {
     }
   }
 
+  /// An assembler for a machine with a different byte size, from 64 for a
+  /// binary MIX to 100 for a decimal one.
+  pub fn with_byte_size(byte_size: isize) -> Result<Assembler, &'static str> {
+    mix::check_byte_size(byte_size)?;
+    let mut assembler = Assembler::new();
+    assembler.byte_size = byte_size;
+    Ok(assembler)
+  }
+
+  pub fn byte_size(&self) -> isize {
+    self.byte_size
+  }
+
   pub fn assemble(&mut self, program: Program) -> Result<(), &'static str> {
     for statement in program.statements.iter() {
       statement.accept(self)?;
@@ -38,6 +55,38 @@ impl Assembler {
     Ok(())
   }
 
+  pub fn listing(&self, source: &str) -> String {
+    let mut locations = HashMap::new();
+    for (location, line) in self.lines.iter() {
+      locations.insert(*line, *location);
+    }
+
+    source
+      .lines()
+      .enumerate()
+      .map(|(i, text)| match locations.get(&(i + 1)) {
+        Some(location) => format!("{:04}  {}", location, text),
+        None => format!("      {}", text),
+      })
+      .collect::<Vec<String>>()
+      .join("\n")
+  }
+
+  pub fn debug_info(&self) -> DebugInfo {
+    // internal names, for local symbols and literals, start with __
+    let symbols = self
+      .symbol_table
+      .iter()
+      .filter(|(name, _)| !name.starts_with("__"))
+      .map(|(name, value)| (name.clone(), *value))
+      .collect();
+
+    DebugInfo {
+      symbols,
+      lines: self.lines.clone(),
+    }
+  }
+
   fn get_value(&mut self, node: &dyn Node) -> isize {
     node.accept(self)
   }
@@ -57,7 +106,7 @@ impl Assembler {
       None => 0,
       Some(a) => self.get_value(a.as_ref()),
     };
-    let address = mix::Address::from_value(address);
+    let address = mix::Address::from_value_in(address, self.byte_size);
 
     let field_spec = match &statement.f_part {
       None => op_info.default_fs,
@@ -115,7 +164,7 @@ impl Assembler {
 
   fn insert_literal_constants(&mut self) {
     for (name, val) in self.literal_constant_table.clone() {
-      let word = mix::Word::from_value(val);
+      let word = mix::Word::from_value_in(val, self.byte_size);
       self.words.insert(self.location_counter, word);
       self.add_symbol_here(name.clone());
       self.location_counter += 1;
@@ -143,7 +192,7 @@ impl Assembler {
     let target = self.symbol_table[&name];
 
     for r in refs {
-      let address = mix::Address::from_value(target);
+      let address = mix::Address::from_value_in(target, self.byte_size);
       let mut word = self.words[r];
       word.sign = address.sign;
       word.bytes[0] = address.bytes[0];
@@ -161,6 +210,7 @@ impl StatementVisitor for Assembler {
     let instruction = self.assemble_mix_statement(statement)?;
     let word = mix::Word::from_instruction(&instruction);
     self.words.insert(self.location_counter, word);
+    self.lines.insert(self.location_counter, statement.line);
     self.deal_with_local_symbol_declaration(statement);
     self.location_counter += 1;
 
@@ -187,8 +237,9 @@ impl StatementVisitor for Assembler {
   fn visit_con_statement(&mut self, statement: &ConStatement) -> Result<(), &'static str> {
     self.deal_with_symbol_declaration(statement);
     let address = self.get_value(statement.address.as_ref());
-    let word = mix::Word::from_value(address);
+    let word = mix::Word::from_value_in(address, self.byte_size);
     self.words.insert(self.location_counter, word);
+    self.lines.insert(self.location_counter, statement.line);
     self.deal_with_local_symbol_declaration(statement);
     self.location_counter += 1;
 
@@ -201,6 +252,7 @@ impl StatementVisitor for Assembler {
     let inner = &char_code[1..char_code.len() - 1];
     let word = mix::Word::from_char_code(inner);
     self.words.insert(self.location_counter, word);
+    self.lines.insert(self.location_counter, statement.line);
     self.deal_with_local_symbol_declaration(statement);
     self.location_counter += 1;
 
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+use serde::{Deserialize, Serialize};
+
+/// What the assembler knows about a program that's useful for debugging it:
+/// the value of each symbol, and the source line that each location was
+/// assembled from. Local symbols and literal constants are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugInfo {
+  pub symbols: HashMap<String, isize>,
+  pub lines: HashMap<usize, usize>,
+}
+
+impl DebugInfo {
+  pub fn load(path: &str) -> Result<DebugInfo, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    bincode::deserialize_from(BufReader::new(file)).map_err(|e| e.to_string())
+  }
+
+  pub fn save(&self, path: &str) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    bincode::serialize_into(BufWriter::new(file), self).map_err(|e| e.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::mixal::{Assembler, Parser};
+
+  #[test]
+  fn test_debug_info() {
+    let source = String::from(
+      "\
+BUF      EQU  2000
+         ORIG 1000
+START    ENTA 5
+2H       STA  BUF+3
+         LDA  =7=
+         HLT
+         END  START
+",
+    );
+    let program = Parser::new(&source).parse().unwrap();
+    let mut assembler = Assembler::new();
+    assembler.assemble(program).unwrap();
+
+    let info = assembler.debug_info();
+    let mut symbols: Vec<(&String, &isize)> = info.symbols.iter().collect();
+    symbols.sort();
+    assert_eq!(
+      symbols,
+      vec![
+        (&String::from("BUF"), &2000),
+        (&String::from("START"), &1000)
+      ]
+    );
+    assert_eq!(info.lines.get(&1000), Some(&3));
+    assert_eq!(info.lines.get(&1003), Some(&6));
+  }
+}
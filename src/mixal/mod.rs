@@ -1,11 +1,12 @@
 mod assembler;
+mod debug_info;
 mod lexer;
-mod op_codes;
 mod parser;
 
+pub use crate::mix::op_codes::OP_CODES;
 pub use assembler::Assembler;
+pub use debug_info::DebugInfo;
 pub use lexer::{Lexer, Token};
-pub use op_codes::OP_CODES;
 pub use parser::{
   Statement, AlfStatement, ConStatement, EndStatement, EquStatement, MixStatement, OrigStatement, Parser,
   Program, StatementVisitor, Node, NodeVisitor, Number, Asterisk, Symbol, LiteralConstant, Expression ,WValue
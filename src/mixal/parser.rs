@@ -15,6 +15,7 @@ pub trait StatementVisitor {
 
 pub trait Statement {
   fn symbol(&self) -> Option<Symbol>;
+  fn line(&self) -> usize;
   fn accept(&self, visitor: &mut dyn StatementVisitor) -> Result<(), &'static str>;
 }
 
@@ -24,6 +25,7 @@ pub struct MixStatement {
   pub a_part: Option<Box<Node>>,
   pub index_part: Option<Box<Node>>,
   pub f_part: Option<Box<Node>>,
+  pub line: usize,
 }
 
 impl Statement for MixStatement {
@@ -31,6 +33,10 @@ impl Statement for MixStatement {
     self.symbol.clone()
   }
 
+  fn line(&self) -> usize {
+    self.line
+  }
+
   fn accept(&self, visitor: &mut dyn StatementVisitor) -> Result<(), &'static str> {
     visitor.visit_mix_statement(self)
   }
@@ -39,6 +45,7 @@ impl Statement for MixStatement {
 pub struct EquStatement {
   pub symbol: Option<Symbol>,
   pub address: Box<Node>,
+  pub line: usize,
 }
 
 impl Statement for EquStatement {
@@ -46,6 +53,10 @@ impl Statement for EquStatement {
     self.symbol.clone()
   }
 
+  fn line(&self) -> usize {
+    self.line
+  }
+
   fn accept(&self, visitor: &mut dyn StatementVisitor) -> Result<(), &'static str> {
     visitor.visit_equ_statement(self)
   }
@@ -54,6 +65,7 @@ impl Statement for EquStatement {
 pub struct OrigStatement {
   pub symbol: Option<Symbol>,
   pub address: Box<Node>,
+  pub line: usize,
 }
 
 impl Statement for OrigStatement {
@@ -61,6 +73,10 @@ impl Statement for OrigStatement {
     self.symbol.clone()
   }
 
+  fn line(&self) -> usize {
+    self.line
+  }
+
   fn accept(&self, visitor: &mut dyn StatementVisitor) -> Result<(), &'static str> {
     visitor.visit_orig_statement(self)
   }
@@ -69,6 +85,7 @@ impl Statement for OrigStatement {
 pub struct ConStatement {
   pub symbol: Option<Symbol>,
   pub address: Box<Node>,
+  pub line: usize,
 }
 
 impl Statement for ConStatement {
@@ -76,6 +93,10 @@ impl Statement for ConStatement {
     self.symbol.clone()
   }
 
+  fn line(&self) -> usize {
+    self.line
+  }
+
   fn accept(&self, visitor: &mut dyn StatementVisitor) -> Result<(), &'static str> {
     visitor.visit_con_statement(self)
   }
@@ -84,6 +105,7 @@ impl Statement for ConStatement {
 pub struct AlfStatement {
   symbol: Option<Symbol>,
   pub char_code: String,
+  pub line: usize,
 }
 
 impl Statement for AlfStatement {
@@ -91,6 +113,10 @@ impl Statement for AlfStatement {
     self.symbol.clone()
   }
 
+  fn line(&self) -> usize {
+    self.line
+  }
+
   fn accept(&self, visitor: &mut dyn StatementVisitor) -> Result<(), &'static str> {
     visitor.visit_alf_statement(self)
   }
@@ -99,6 +125,7 @@ impl Statement for AlfStatement {
 pub struct EndStatement {
   symbol: Option<Symbol>,
   pub address: Box<Node>,
+  pub line: usize,
 }
 
 impl Statement for EndStatement {
@@ -106,6 +133,10 @@ impl Statement for EndStatement {
     self.symbol.clone()
   }
 
+  fn line(&self) -> usize {
+    self.line
+  }
+
   fn accept(&self, visitor: &mut dyn StatementVisitor) -> Result<(), &'static str> {
     visitor.visit_end_statement(self)
   }
@@ -262,6 +293,24 @@ impl Parser {
     Ok(program)
   }
 
+  /// Parses the whole input as one expression, for things like debugger
+  /// conditions that aren't part of a program.
+  pub fn parse_single_expression(&mut self) -> Result<Box<dyn Node>, &'static str> {
+    self.swallow_whitespace();
+
+    let expression = match self.parse_expression()? {
+      None => return Err("expected expression"),
+      Some(expression) => expression,
+    };
+
+    let lexeme = self.scan_ignore_whitespace();
+    if lexeme.token != Token::EOF && lexeme.token != Token::EOL {
+      return Err("expected end of expression");
+    }
+
+    Ok(expression)
+  }
+
   fn parse_statement<'a>(&mut self) -> Result<Box<dyn Statement>, &'a str> {
     let lexeme = self.scan();
     if lexeme.token == Token::EOL {
@@ -269,16 +318,17 @@ impl Parser {
     }
     self.unscan();
 
+    let line = lexeme.line;
     let symbol = self.parse_symbol();
     let opcode = self.parse_opcode()?;
 
     let statement: Box<Statement> = match opcode.as_str() {
-      "EQU" => Box::new(self.parse_equ_statement(symbol)?),
-      "ORIG" => Box::new(self.parse_orig_statement(symbol)?),
-      "CON" => Box::new(self.parse_con_statement(symbol)?),
-      "ALF" => Box::new(self.parse_alf_statement(symbol)?),
-      "END" => Box::new(self.parse_end_statement(symbol)?),
-      _ => Box::new(self.parse_mix_statement(symbol, opcode)?),
+      "EQU" => Box::new(self.parse_equ_statement(symbol, line)?),
+      "ORIG" => Box::new(self.parse_orig_statement(symbol, line)?),
+      "CON" => Box::new(self.parse_con_statement(symbol, line)?),
+      "ALF" => Box::new(self.parse_alf_statement(symbol, line)?),
+      "END" => Box::new(self.parse_end_statement(symbol, line)?),
+      _ => Box::new(self.parse_mix_statement(symbol, opcode, line)?),
     };
 
     Ok(statement)
@@ -288,6 +338,7 @@ impl Parser {
     &mut self,
     symbol: Option<Symbol>,
     op: String,
+    line: usize,
   ) -> Result<MixStatement, &'a str> {
     if !OP_CODES.contains_key(&op[..]) {
       eprintln!("opcode, {}", op);
@@ -311,10 +362,15 @@ impl Parser {
       a_part,
       index_part,
       f_part,
+      line,
     })
   }
 
-  fn parse_equ_statement<'a>(&mut self, symbol: Option<Symbol>) -> Result<EquStatement, &'a str> {
+  fn parse_equ_statement<'a>(
+    &mut self,
+    symbol: Option<Symbol>,
+    line: usize,
+  ) -> Result<EquStatement, &'a str> {
     self.swallow_whitespace();
 
     let w_value = self.parse_w_value()?;
@@ -331,10 +387,15 @@ impl Parser {
     Ok(EquStatement {
       symbol,
       address: Box::new(address),
+      line,
     })
   }
 
-  fn parse_orig_statement<'a>(&mut self, symbol: Option<Symbol>) -> Result<OrigStatement, &'a str> {
+  fn parse_orig_statement<'a>(
+    &mut self,
+    symbol: Option<Symbol>,
+    line: usize,
+  ) -> Result<OrigStatement, &'a str> {
     self.swallow_whitespace();
 
     let w_value = self.parse_w_value()?;
@@ -351,10 +412,15 @@ impl Parser {
     Ok(OrigStatement {
       symbol,
       address: Box::new(address),
+      line,
     })
   }
 
-  fn parse_con_statement<'a>(&mut self, symbol: Option<Symbol>) -> Result<ConStatement, &'a str> {
+  fn parse_con_statement<'a>(
+    &mut self,
+    symbol: Option<Symbol>,
+    line: usize,
+  ) -> Result<ConStatement, &'a str> {
     self.swallow_whitespace();
 
     let w_value = self.parse_w_value()?;
@@ -371,10 +437,15 @@ impl Parser {
     Ok(ConStatement {
       symbol,
       address: Box::new(address),
+      line,
     })
   }
 
-  fn parse_alf_statement<'a>(&mut self, symbol: Option<Symbol>) -> Result<AlfStatement, &'a str> {
+  fn parse_alf_statement<'a>(
+    &mut self,
+    symbol: Option<Symbol>,
+    line: usize,
+  ) -> Result<AlfStatement, &'a str> {
     self.swallow_whitespace();
 
     let lexeme = self.scan();
@@ -389,10 +460,18 @@ impl Parser {
       return Err("expected EOL");
     }
 
-    Ok(AlfStatement { symbol, char_code })
+    Ok(AlfStatement {
+      symbol,
+      char_code,
+      line,
+    })
   }
 
-  fn parse_end_statement<'a>(&mut self, symbol: Option<Symbol>) -> Result<EndStatement, &'a str> {
+  fn parse_end_statement<'a>(
+    &mut self,
+    symbol: Option<Symbol>,
+    line: usize,
+  ) -> Result<EndStatement, &'a str> {
     self.swallow_whitespace();
 
     let w_value = self.parse_w_value()?;
@@ -409,6 +488,7 @@ impl Parser {
     Ok(EndStatement {
       symbol,
       address: Box::new(address),
+      line,
     })
   }
 
@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use crate::mix;
 use crate::operations::Operation;
@@ -14,9 +14,9 @@ impl<'a> Enter<'a> {
 }
 
 impl<'a> Operation for Enter<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let value = computer.get_indexed_address_value(self.instruction);
-    let mut word = mix::Word::from_value(value);
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let value = computer.get_indexed_address_value(self.instruction)?;
+    let mut word = mix::Word::from_value_in(value, computer.byte_size);
     if value == 0 {
       word.sign = self.instruction.address.sign;
     }
@@ -30,14 +30,16 @@ impl<'a> Operation for Enter<'a> {
         computer.accumulator = word;
       }
       mix::op_codes::ENTX => {
-        computer.extension.write(word);
+        computer.write_extension(self.instruction, word)?;
       }
       mix::op_codes::ENT1...mix::op_codes::ENT6 => {
         let index = (self.instruction.operation - mix::op_codes::ENT1) as usize;
         computer.indexes[index] = word.cast_to_address();
       }
-      _ => panic!("unknown enter operation {}", self.instruction.operation),
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     }
+
+    Ok(())
   }
 }
 
@@ -119,7 +121,11 @@ mod tests {
       computer.indexes[1] = mix::Address::zero();
       computer.indexes[2] = mix::Address::negative_zero();
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc)
     }
@@ -199,7 +205,11 @@ mod tests {
       computer.indexes[1] = mix::Address::zero();
       computer.indexes[2] = mix::Address::negative_zero();
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.extension.read(), *expected_ext)
     }
@@ -276,7 +286,11 @@ mod tests {
       computer.indexes[1] = mix::Address::zero();
       computer.indexes[2] = mix::Address::negative_zero();
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.indexes[(index - 1) as usize], *expected_reg)
     }
@@ -356,7 +370,11 @@ mod tests {
       computer.indexes[1] = mix::Address::zero();
       computer.indexes[2] = mix::Address::negative_zero();
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc)
     }
@@ -436,7 +454,11 @@ mod tests {
       computer.indexes[1] = mix::Address::zero();
       computer.indexes[2] = mix::Address::negative_zero();
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.extension.read(), *expected_ext)
     }
@@ -513,7 +535,11 @@ mod tests {
       computer.indexes[1] = mix::Address::zero();
       computer.indexes[2] = mix::Address::negative_zero();
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.indexes[(index - 1) as usize], *expected_reg)
     }
@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use crate::mix;
 use crate::operations::Operation;
@@ -14,8 +14,9 @@ impl<'a> Increase<'a> {
 }
 
 impl<'a> Operation for Increase<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let mut value = computer.get_indexed_address_value(self.instruction);
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let mut value = computer.get_indexed_address_value(self.instruction)?;
 
     if self.instruction.modification == 1 {
       value *= -1;
@@ -26,13 +27,13 @@ impl<'a> Operation for Increase<'a> {
     // TODO: we can decide which register to read/write as runtime?
     match self.instruction.operation {
       mix::op_codes::INCA => {
-        let sum = computer.accumulator.value() + value;
+        let sum = computer.accumulator.value_in(byte_size) + value;
 
-        let mut result = if mix::Word::fits_in_word(sum) {
-          mix::Word::from_value(sum)
+        let mut result = if mix::Word::fits_in(sum, byte_size) {
+          mix::Word::from_value_in(sum, byte_size)
         } else {
           computer.overflow = true;
-          mix::Word::from_value_with_overflow(sum)
+          mix::Word::from_value_with_overflow_in(sum, byte_size)
         };
 
         if sum == 0 {
@@ -43,30 +44,33 @@ impl<'a> Operation for Increase<'a> {
         computer.accumulator = result;
       }
       mix::op_codes::INCX => {
-        let sum = computer.extension.read().value() + value;
+        let extension = computer.read_extension(self.instruction)?;
+        let sum = extension.value_in(byte_size) + value;
 
-        let mut result = if mix::Word::fits_in_word(sum) {
-          mix::Word::from_value(sum)
+        let mut result = if mix::Word::fits_in(sum, byte_size) {
+          mix::Word::from_value_in(sum, byte_size)
         } else {
           computer.overflow = true;
-          mix::Word::from_value_with_overflow(sum)
+          mix::Word::from_value_with_overflow_in(sum, byte_size)
         };
 
         if sum == 0 {
           // keep the sign unchanged (pg. 131)
-          result.sign = computer.extension.read().sign;
+          result.sign = extension.sign;
         }
 
-        computer.extension.write(result);
+        computer.write_extension(self.instruction, result)?;
       }
       mix::op_codes::INC1...mix::op_codes::INC6 => {
         let index = (self.instruction.operation - mix::op_codes::INC1) as usize;
         let i = computer.indexes[index];
-        let sum = i.value() + value;
-        computer.indexes[index] = mix::Address::from_value(sum);
+        let sum = i.value_in(byte_size) + value;
+        computer.indexes[index] = mix::Address::from_value_in(sum, byte_size);
       }
-      _ => panic!("unknown op code"),
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     }
+
+    Ok(())
   }
 }
 
@@ -103,7 +107,11 @@ mod tests {
       let mut computer = Computer::new();
       computer.accumulator = mix::Word::from_value(1000);
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc)
     }
@@ -138,7 +146,11 @@ mod tests {
       let mut computer = Computer::new();
       computer.extension.write(mix::Word::from_value(1000));
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.extension.read(), *expected_ext)
     }
@@ -213,7 +225,11 @@ mod tests {
       let mut computer = Computer::new();
       computer.indexes[(index - 1) as usize] = mix::Address::from_value(1000);
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.indexes[(index - 1) as usize], *expected_reg)
     }
@@ -248,7 +264,11 @@ mod tests {
       let mut computer = Computer::new();
       computer.accumulator = mix::Word::from_value(1000);
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc)
     }
@@ -283,7 +303,11 @@ mod tests {
       let mut computer = Computer::new();
       computer.extension.write(mix::Word::from_value(1000));
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.extension.read(), *expected_ext)
     }
@@ -358,7 +382,11 @@ mod tests {
       let mut computer = Computer::new();
       computer.indexes[(index - 1) as usize] = mix::Address::from_value(1000);
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.indexes[(index - 1) as usize], *expected_reg)
     }
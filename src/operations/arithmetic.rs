@@ -1,7 +1,9 @@
 mod addition;
 mod multiplication;
 mod division;
+pub mod floating_point;
 
 pub use addition::Addition;
 pub use multiplication::Multiplication;
 pub use division::Division;
+pub use floating_point::{FloatingAddition, FloatingDivision, FloatingMultiplication};
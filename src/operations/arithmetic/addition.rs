@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 
 use crate::mix;
@@ -14,24 +14,25 @@ impl<'a> Addition<'a> {
 }
 
 impl<'a> Operation for Addition<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction) as usize;
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let address = computer.get_memory_address(self.instruction)?;
 
-    let word = computer.memory[address].read();
+    let word = computer.read_memory(self.instruction, address)?;
     let word = word.apply_field_spec(self.instruction.modification);
-    let mut value = word.value();
+    let mut value = word.value_in(byte_size);
 
     if self.instruction.operation == mix::op_codes::SUB {
       value *= -1;
     }
 
-    let sum = computer.accumulator.value() + value;
+    let sum = computer.accumulator.value_in(byte_size) + value;
 
-    let mut result = if mix::Word::fits_in_word(sum) {
-      mix::Word::from_value(sum)
+    let mut result = if mix::Word::fits_in(sum, byte_size) {
+      mix::Word::from_value_in(sum, byte_size)
     } else {
       computer.overflow = true;
-      mix::Word::from_value_with_overflow(sum)
+      mix::Word::from_value_with_overflow_in(sum, byte_size)
     };
 
     if sum == 0 {
@@ -40,6 +41,12 @@ impl<'a> Operation for Addition<'a> {
     }
 
     computer.accumulator = result;
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    2
   }
 }
 
@@ -135,7 +142,11 @@ mod tests {
       computer.accumulator = *prev_acc;
       computer.memory[1000].write(*prev_mem);
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc);
       assert_eq!(computer.overflow, *expected_ov);
@@ -227,7 +238,11 @@ mod tests {
       computer.accumulator = *prev_acc;
       computer.memory[1000].write(*prev_mem);
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc);
       assert_eq!(computer.overflow, *expected_ov);
@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use crate::mix;
 use crate::operations::Operation;
@@ -14,27 +14,33 @@ impl<'a> Division<'a> {
 }
 
 impl<'a> Operation for Division<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction) as usize;
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let address = computer.get_memory_address(self.instruction)?;
 
-    let word = computer.memory[address].read();
+    let word = computer.read_memory(self.instruction, address)?;
     let word = word.apply_field_spec(self.instruction.modification);
 
-    if computer.accumulator.value().abs() >= word.value().abs() {
+    if computer.accumulator.value_in(byte_size).abs() >= word.value_in(byte_size).abs() {
       eprintln!("UNDEFINED BEHAVIOUR");
       eprintln!(
         "{} >= {}",
-        computer.accumulator.value().abs(),
-        word.value().abs()
+        computer.accumulator.value_in(byte_size).abs(),
+        word.value_in(byte_size).abs()
       );
       // the values of rA and rX is undefined behaviour (pg. 131) - we'll just zero them
       computer.accumulator = mix::Word::zero();
-      computer.extension.write(mix::Word::zero());
+      computer.write_extension(self.instruction, mix::Word::zero())?;
       computer.overflow = true;
-      return;
+      return Ok(());
     }
 
-    let rax = computer.accumulator.value() * 1073741824 + computer.extension.read().value().abs();
+    let extension = computer.read_extension(self.instruction)?;
+    // rAX can be too big for an isize on a decimal MIX
+    let word_size = (byte_size as i128).pow(5);
+    let rax = computer.accumulator.value_in(byte_size) as i128 * word_size
+      + extension.value_in(byte_size).abs() as i128;
+    let divisor = word.value_in(byte_size) as i128;
 
     let sign = if word.sign == computer.accumulator.sign {
       mix::Sign::Positive
@@ -42,17 +48,21 @@ impl<'a> Operation for Division<'a> {
       mix::Sign::Negative
     };
 
-    let quotient = rax / word.value();
-    let remainder = rax.abs() % word.value().abs();
+    let quotient = (rax / divisor) as isize;
+    let remainder = (rax.abs() % divisor.abs()) as isize;
 
-    let mut new_acc = mix::Word::from_value(quotient);
+    let mut new_acc = mix::Word::from_value_in(quotient, byte_size);
     new_acc.sign = sign;
 
-    let mut new_ext = mix::Word::from_value(remainder);
+    let mut new_ext = mix::Word::from_value_in(remainder, byte_size);
     new_ext.sign = computer.accumulator.sign;
 
     computer.accumulator = new_acc;
-    computer.extension.write(new_ext);
+    computer.write_extension(self.instruction, new_ext)
+  }
+
+  fn time(&self) -> usize {
+    12
   }
 }
 
@@ -189,7 +199,11 @@ mod tests {
       computer.extension.write(*prev_ext);
       computer.memory[1000].write(*prev_mem);
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc);
       assert_eq!(computer.extension.read(), *expected_ext);
@@ -0,0 +1,334 @@
+use crate::computer::{Computer, MixFault};
+
+use crate::mix;
+use crate::operations::Operation;
+
+// single precision floating point numbers (pg. 214) are stored as
+// ± e f f f f, where e is the exponent in excess-q and f is the fraction
+pub const FRACTION_BYTES: u32 = 4;
+
+pub struct FloatingAddition<'a> {
+  instruction: &'a mix::Instruction,
+}
+
+impl<'a> FloatingAddition<'a> {
+  pub fn new(instruction: &'a mix::Instruction) -> FloatingAddition<'a> {
+    FloatingAddition { instruction }
+  }
+}
+
+impl<'a> Operation for FloatingAddition<'a> {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let address = computer.get_memory_address(self.instruction)?;
+    let word = computer.read_memory(self.instruction, address)?;
+
+    let u = unpack(&computer.accumulator, byte_size);
+    let mut v = unpack(&word, byte_size);
+    if self.instruction.operation == mix::op_codes::FSUB {
+      v.0 = -v.0;
+    }
+
+    let ((fu, eu), (fv, ev)) = if u.1 < v.1 { (v, u) } else { (u, v) };
+
+    // if the exponents are too far apart, v can't affect the result (Algorithm A, step A4)
+    let difference = (eu - ev) as u32;
+    let (fraction, digits) = if difference >= FRACTION_BYTES + 2 {
+      (fu, FRACTION_BYTES)
+    } else {
+      (
+        fu * power(difference, byte_size) + fv,
+        FRACTION_BYTES + difference,
+      )
+    };
+
+    let (result, overflow) = normalize(fraction, digits, eu, byte_size);
+    computer.accumulator = result;
+    if overflow {
+      computer.overflow = true;
+    }
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    4
+  }
+}
+
+pub struct FloatingMultiplication<'a> {
+  instruction: &'a mix::Instruction,
+}
+
+impl<'a> FloatingMultiplication<'a> {
+  pub fn new(instruction: &'a mix::Instruction) -> FloatingMultiplication<'a> {
+    FloatingMultiplication { instruction }
+  }
+}
+
+impl<'a> Operation for FloatingMultiplication<'a> {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let address = computer.get_memory_address(self.instruction)?;
+    let word = computer.read_memory(self.instruction, address)?;
+
+    let (fu, eu) = unpack(&computer.accumulator, byte_size);
+    let (fv, ev) = unpack(&word, byte_size);
+
+    let (result, overflow) = normalize(
+      fu * fv,
+      2 * FRACTION_BYTES,
+      eu + ev - excess(byte_size),
+      byte_size,
+    );
+    computer.accumulator = result;
+    if overflow {
+      computer.overflow = true;
+    }
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    9
+  }
+}
+
+pub struct FloatingDivision<'a> {
+  instruction: &'a mix::Instruction,
+}
+
+impl<'a> FloatingDivision<'a> {
+  pub fn new(instruction: &'a mix::Instruction) -> FloatingDivision<'a> {
+    FloatingDivision { instruction }
+  }
+}
+
+impl<'a> Operation for FloatingDivision<'a> {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let address = computer.get_memory_address(self.instruction)?;
+    let word = computer.read_memory(self.instruction, address)?;
+
+    let (fu, eu) = unpack(&computer.accumulator, byte_size);
+    let (fv, ev) = unpack(&word, byte_size);
+
+    if fv == 0 {
+      // division by zero is undefined - we'll zero rA, as with DIV
+      computer.accumulator = mix::Word::zero();
+      computer.overflow = true;
+      return Ok(());
+    }
+
+    // carry enough extra digits that the truncated quotient still rounds correctly
+    let digits = FRACTION_BYTES + 6;
+    let fraction = fu * power(digits, byte_size) / fv;
+
+    let (result, overflow) = normalize(fraction, digits, eu - ev + excess(byte_size), byte_size);
+    computer.accumulator = result;
+    if overflow {
+      computer.overflow = true;
+    }
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    11
+  }
+}
+
+/// Splits a floating point word into a signed fraction (scaled up by b^4)
+/// and its exponent.
+pub fn unpack(word: &mix::Word, byte_size: isize) -> (i128, isize) {
+  let mut fraction = 0;
+  for byte in word.bytes[1..].iter() {
+    fraction = fraction * byte_size as i128 + *byte as i128;
+  }
+
+  if word.sign == mix::Sign::Negative {
+    fraction = -fraction;
+  }
+
+  (fraction, word.bytes[0] as isize)
+}
+
+/// Packs `fraction / b^digits * b^(exponent - q)` into a normalized,
+/// rounded floating point word (Algorithm N, pg. 216). Also returns whether
+/// the exponent overflowed or underflowed.
+pub fn normalize(
+  fraction: i128,
+  digits: u32,
+  exponent: isize,
+  byte_size: isize,
+) -> (mix::Word, bool) {
+  if fraction == 0 {
+    return (mix::Word::zero(), false);
+  }
+
+  let base = byte_size as i128;
+  let sign = if fraction < 0 {
+    mix::Sign::Negative
+  } else {
+    mix::Sign::Positive
+  };
+  let mut fraction = fraction.abs();
+  let mut digits = digits;
+  let mut exponent = exponent;
+
+  // scale right
+  while fraction >= power(digits, byte_size) {
+    digits += 1;
+    exponent += 1;
+  }
+
+  // scale left
+  while fraction < power(digits - 1, byte_size) {
+    fraction *= base;
+    exponent -= 1;
+  }
+
+  // round
+  let extra = power(digits - FRACTION_BYTES, byte_size);
+  let remainder = fraction % extra;
+  fraction /= extra;
+  if 2 * remainder >= extra {
+    fraction += 1;
+  }
+  if fraction == power(FRACTION_BYTES, byte_size) {
+    fraction /= base;
+    exponent += 1;
+  }
+
+  let overflow = !(0..byte_size).contains(&exponent);
+
+  let mut word = mix::Word::zero();
+  word.sign = sign;
+  word.bytes[0] = exponent.rem_euclid(byte_size) as u8;
+  for i in 0..FRACTION_BYTES as usize {
+    word.bytes[FRACTION_BYTES as usize - i] = (fraction % base) as u8;
+    fraction /= base;
+  }
+
+  (word, overflow)
+}
+
+/// The exponent bias q, which is half the byte size.
+pub fn excess(byte_size: isize) -> isize {
+  byte_size / 2
+}
+
+pub fn power(digits: u32, byte_size: isize) -> i128 {
+  (byte_size as i128).pow(digits)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_floating_point() {
+    let one = mix::Word {
+      bytes: [33, 1, 0, 0, 0],
+      sign: mix::Sign::Positive,
+    };
+    let half = mix::Word {
+      bytes: [32, 32, 0, 0, 0],
+      sign: mix::Sign::Positive,
+    };
+    let two = mix::Word {
+      bytes: [33, 2, 0, 0, 0],
+      sign: mix::Sign::Positive,
+    };
+    let third = mix::Word {
+      bytes: [32, 21, 21, 21, 21],
+      sign: mix::Sign::Positive,
+    };
+    let big = mix::Word {
+      bytes: [63, 1, 0, 0, 0],
+      sign: mix::Sign::Positive,
+    };
+
+    let tests = [
+      (one, one, mix::op_codes::FADD, two, false),
+      (
+        one,
+        one.toggle_sign(),
+        mix::op_codes::FADD,
+        mix::Word::zero(),
+        false,
+      ),
+      (one, half, mix::op_codes::FSUB, half, false),
+      (half, one, mix::op_codes::FSUB, half.toggle_sign(), false),
+      (two, half, mix::op_codes::FMUL, one, false),
+      (
+        one,
+        two.toggle_sign(),
+        mix::op_codes::FDIV,
+        half.toggle_sign(),
+        false,
+      ),
+      (
+        one,
+        mix::Word {
+          bytes: [33, 3, 0, 0, 0],
+          sign: mix::Sign::Positive,
+        },
+        mix::op_codes::FDIV,
+        third,
+        false,
+      ),
+      (
+        third,
+        mix::Word {
+          bytes: [28, 32, 0, 0, 0],
+          sign: mix::Sign::Positive,
+        },
+        mix::op_codes::FADD,
+        mix::Word {
+          bytes: [32, 21, 21, 21, 22],
+          sign: mix::Sign::Positive,
+        },
+        false,
+      ),
+      (
+        big,
+        big,
+        mix::op_codes::FMUL,
+        mix::Word {
+          bytes: [29, 1, 0, 0, 0],
+          sign: mix::Sign::Positive,
+        },
+        true,
+      ),
+      (
+        one,
+        mix::Word::zero(),
+        mix::op_codes::FDIV,
+        mix::Word::zero(),
+        true,
+      ),
+    ];
+
+    for (prev_acc, prev_mem, operation, expected_acc, expected_ov) in &tests {
+      let mut computer = Computer::new();
+      computer.accumulator = *prev_acc;
+      computer.memory[1000].write(*prev_mem);
+
+      let instruction = mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 6,
+        operation: *operation,
+      };
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
+
+      assert_eq!(computer.accumulator, *expected_acc);
+      assert_eq!(computer.overflow, *expected_ov);
+    }
+  }
+}
@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use crate::mix;
 use crate::operations::Operation;
@@ -14,10 +14,11 @@ impl<'a> Multiplication<'a> {
 }
 
 impl<'a> Operation for Multiplication<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction) as usize;
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let address = computer.get_memory_address(self.instruction)?;
 
-    let word = computer.memory[address].read();
+    let word = computer.read_memory(self.instruction, address)?;
     let word = word.apply_field_spec(self.instruction.modification);
 
     let sign = if word.sign == computer.accumulator.sign {
@@ -26,18 +27,25 @@ impl<'a> Operation for Multiplication<'a> {
       mix::Sign::Negative
     };
 
-    let product = computer.accumulator.value() * word.value();
-    let acc_result = product / 1073741824; // TODO: base on byte size
-    let ext_result = product % 1073741824;
+    // the product can be too big for an isize on a decimal MIX
+    let word_size = (byte_size as i128).pow(5);
+    let product =
+      computer.accumulator.value_in(byte_size) as i128 * word.value_in(byte_size) as i128;
+    let acc_result = (product / word_size) as isize;
+    let ext_result = (product % word_size) as isize;
 
-    let mut new_acc = mix::Word::from_value(acc_result);
+    let mut new_acc = mix::Word::from_value_in(acc_result, byte_size);
     new_acc.sign = sign;
 
-    let mut new_ext = mix::Word::from_value(ext_result);
+    let mut new_ext = mix::Word::from_value_in(ext_result, byte_size);
     new_ext.sign = sign;
 
     computer.accumulator = new_acc;
-    computer.extension.write(new_ext);
+    computer.write_extension(self.instruction, new_ext)
+  }
+
+  fn time(&self) -> usize {
+    10
   }
 }
 
@@ -124,7 +132,11 @@ mod tests {
       computer.accumulator = *prev_acc;
       computer.memory[1000].write(*prev_mem);
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc);
       assert_eq!(computer.extension.read(), *expected_ext);
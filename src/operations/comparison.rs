@@ -1,8 +1,12 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
+use super::arithmetic::floating_point;
 use super::Operation;
 use crate::mix;
 
+// FCMP compares using ε = CONTENTS(EPSILON), where EPSILON is a fixed location
+const EPSILON: isize = 0;
+
 pub struct Compare<'a> {
   instruction: &'a mix::Instruction,
 }
@@ -14,24 +18,27 @@ impl<'a> Compare<'a> {
 }
 
 impl<'a> Operation for Compare<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction) as usize;
-    let word = computer.memory[address].read().apply_field_spec(self.instruction.modification);
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let address = computer.get_memory_address(self.instruction)?;
+    let word = computer
+      .read_memory(self.instruction, address)?
+      .apply_field_spec(self.instruction.modification);
 
     let register = match self.instruction.operation {
       mix::op_codes::CMPA => computer.accumulator,
-      mix::op_codes::CMPX => computer.extension.read(),
+      mix::op_codes::CMPX => computer.read_extension(self.instruction)?,
       mix::op_codes::CMP1...mix::op_codes::CMP6 => {
         let index = (self.instruction.operation - mix::op_codes::CMP1) as usize;
         computer.indexes[index].cast_to_word()
       }
-      _ => panic!("unknown compare operation: {}", self.instruction.operation),
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     };
 
     let register = register.apply_field_spec(self.instruction.modification);
 
-    let w_val = word.value();
-    let r_val = register.value();
+    let w_val = word.value_in(byte_size);
+    let r_val = register.value_in(byte_size);
 
     let result = if r_val < w_val {
       mix::Comparison::Less
@@ -42,6 +49,82 @@ impl<'a> Operation for Compare<'a> {
     };
 
     computer.comparison = result;
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    2
+  }
+}
+
+pub struct FloatingCompare<'a> {
+  instruction: &'a mix::Instruction,
+}
+
+impl<'a> FloatingCompare<'a> {
+  pub fn new(instruction: &'a mix::Instruction) -> FloatingCompare<'a> {
+    FloatingCompare { instruction }
+  }
+}
+
+impl<'a> Operation for FloatingCompare<'a> {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let address = computer.get_memory_address(self.instruction)?;
+    let word = computer.read_memory(self.instruction, address)?;
+    let epsilon = computer.read_memory(self.instruction, EPSILON)?;
+    let byte_size = computer.byte_size;
+
+    let (fu, eu) = floating_point::unpack(&computer.accumulator, byte_size);
+    let (fv, ev) = floating_point::unpack(&word, byte_size);
+    let (fe, ee) = floating_point::unpack(&epsilon, byte_size);
+
+    // line both fractions up against the larger exponent, with 6 extra digits
+    // - any further apart than that and the smaller one can't matter
+    let exponent = eu.max(ev);
+    let align = |fraction: i128, e: isize| {
+      let gap = (exponent - e) as u32;
+      if gap >= 6 {
+        0
+      } else {
+        fraction * floating_point::power(6 - gap, byte_size)
+      }
+    };
+    let difference = align(fv, ev) - align(fu, eu);
+
+    // u ~ v unless |v - u| > ε * max(b^(eu-q), b^(ev-q))
+    let scale = ee - floating_point::excess(byte_size) + 6;
+    computer.comparison = if !exceeds_epsilon(difference, fe.abs(), scale, byte_size) {
+      mix::Comparison::Equal
+    } else if difference > 0 {
+      mix::Comparison::Less
+    } else {
+      mix::Comparison::Greater
+    };
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    4
+  }
+}
+
+/// Whether `|difference| > epsilon * b^scale`, without building numbers too
+/// big to hold.
+fn exceeds_epsilon(difference: i128, epsilon: i128, scale: isize, byte_size: isize) -> bool {
+  let difference = difference.abs();
+
+  if difference == 0 {
+    false
+  } else if epsilon == 0 {
+    true
+  } else if scale >= 0 {
+    // the difference is always less than b^11
+    scale < 11 && difference > epsilon * floating_point::power(scale as u32, byte_size)
+  } else {
+    // epsilon's fraction is always less than b^4
+    scale <= -4 || difference * floating_point::power(-scale as u32, byte_size) > epsilon
   }
 }
 
@@ -100,7 +183,11 @@ mod tests {
       computer.memory[0].write(mix::Word::zero());
       computer.memory[2000].write(mix::Word::from_value(10));
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.comparison, *expected_cmp);
     }
@@ -157,7 +244,11 @@ mod tests {
       computer.memory[0].write(mix::Word::zero());
       computer.memory[2000].write(mix::Word::from_value(10));
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.comparison, *expected_cmp);
     }
@@ -240,9 +331,75 @@ mod tests {
       computer.memory[0].write(mix::Word::zero());
       computer.memory[2000].write(mix::Word::from_value(10));
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
+
+      assert_eq!(computer.comparison, *expected_cmp);
+    }
+  }
+
+  #[test]
+  fn test_fcmp() {
+    let one = mix::Word {
+      bytes: [33, 1, 0, 0, 0],
+      sign: mix::Sign::Positive,
+    };
+    let just_over_one = mix::Word {
+      bytes: [33, 1, 0, 0, 1],
+      sign: mix::Sign::Positive,
+    };
+    let two = mix::Word {
+      bytes: [33, 2, 0, 0, 0],
+      sign: mix::Sign::Positive,
+    };
+    let small = mix::Word {
+      bytes: [31, 1, 0, 0, 0],
+      sign: mix::Sign::Positive,
+    };
+    let tiny = mix::Word {
+      bytes: [27, 1, 0, 0, 0],
+      sign: mix::Sign::Positive,
+    };
+
+    let tests = [
+      (one, one, mix::Word::zero(), mix::Comparison::Equal),
+      (one, two, mix::Word::zero(), mix::Comparison::Less),
+      (two, one, mix::Word::zero(), mix::Comparison::Greater),
+      (one, just_over_one, mix::Word::zero(), mix::Comparison::Less),
+      (one, just_over_one, small, mix::Comparison::Equal),
+      (just_over_one, one, small, mix::Comparison::Equal),
+      (one, just_over_one, tiny, mix::Comparison::Less),
+      (one, tiny, small, mix::Comparison::Greater),
+      (
+        tiny.toggle_sign(),
+        tiny,
+        mix::Word::zero(),
+        mix::Comparison::Less,
+      ),
+    ];
+
+    for (acc_before, word, epsilon, expected_cmp) in &tests {
+      let mut computer = Computer::new();
+      computer.accumulator = *acc_before;
+      computer.memory[0].write(*epsilon);
+      computer.memory[2000].write(*word);
+
+      let instruction = mix::Instruction {
+        address: mix::Address::from_value(2000),
+        index_specification: 0,
+        modification: 6,
+        operation: mix::op_codes::FCMP,
+      };
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.comparison, *expected_cmp);
     }
   }
-}
\ No newline at end of file
+}
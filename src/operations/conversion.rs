@@ -1,5 +1,9 @@
 mod convert_to_characters;
+mod convert_to_fixed_point;
+mod convert_to_floating_point;
 mod convert_to_numeric;
 
 pub use convert_to_characters::ConvertToCharacters;
+pub use convert_to_fixed_point::ConvertToFixedPoint;
+pub use convert_to_floating_point::ConvertToFloatingPoint;
 pub use convert_to_numeric::ConvertToNumeric;
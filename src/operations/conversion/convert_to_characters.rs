@@ -1,23 +1,26 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
+use crate::mix;
 use crate::operations::Operation;
 
-pub struct ConvertToCharacters {}
+pub struct ConvertToCharacters<'a> {
+  instruction: &'a mix::Instruction,
+}
 
-impl ConvertToCharacters {
-  pub fn new() -> ConvertToCharacters {
-    ConvertToCharacters {}
+impl<'a> ConvertToCharacters<'a> {
+  pub fn new(instruction: &'a mix::Instruction) -> ConvertToCharacters<'a> {
+    ConvertToCharacters { instruction }
   }
 }
 
-impl Operation for ConvertToCharacters {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let mut value = computer.accumulator.value();
+impl<'a> Operation for ConvertToCharacters<'a> {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let mut value = computer.accumulator.value_in(computer.byte_size);
     if value < 0 {
       value = -value;
     }
 
-    let mut extension = computer.extension.read();
+    let mut extension = computer.read_extension(self.instruction)?;
 
     for i in 0..10 {
       let b = (value % 10 + 30) as u8;
@@ -29,6 +32,10 @@ impl Operation for ConvertToCharacters {
       }
     }
 
-    computer.extension.write(extension);
+    computer.write_extension(self.instruction, extension)
+  }
+
+  fn time(&self) -> usize {
+    10
   }
 }
@@ -0,0 +1,129 @@
+use crate::computer::{Computer, MixFault};
+
+use crate::mix;
+use crate::operations::arithmetic::floating_point;
+use crate::operations::Operation;
+
+#[derive(Default)]
+pub struct ConvertToFixedPoint {}
+
+impl ConvertToFixedPoint {
+  pub fn new() -> ConvertToFixedPoint {
+    ConvertToFixedPoint {}
+  }
+}
+
+impl Operation for ConvertToFixedPoint {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let (fraction, exponent) = floating_point::unpack(&computer.accumulator, byte_size);
+    let fraction = fraction.abs();
+
+    // the value is fraction * b^shift, rounded to the nearest integer
+    let shift =
+      exponent - floating_point::excess(byte_size) - floating_point::FRACTION_BYTES as isize;
+    let value = if shift >= 0 {
+      // anything shifted past 5 bytes is lost to overflow anyway
+      fraction * floating_point::power(shift.min(5) as u32, byte_size)
+    } else if shift < -5 {
+      0
+    } else {
+      let divisor = floating_point::power(-shift as u32, byte_size);
+      let mut value = fraction / divisor;
+      if 2 * (fraction % divisor) >= divisor {
+        value += 1;
+      }
+      value
+    };
+
+    let limit = floating_point::power(5, byte_size);
+    if value >= limit {
+      computer.overflow = true;
+    }
+
+    let sign = computer.accumulator.sign;
+    computer.accumulator = mix::Word::from_value_in((value % limit) as isize, byte_size);
+    computer.accumulator.sign = sign;
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    3
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fix() {
+    let tests = [
+      (mix::Word::zero(), mix::Word::zero(), false),
+      (
+        mix::Word {
+          bytes: [33, 1, 0, 0, 0],
+          sign: mix::Sign::Positive,
+        },
+        mix::Word::from_value(1),
+        false,
+      ),
+      (
+        mix::Word {
+          bytes: [34, 2, 2, 0, 0],
+          sign: mix::Sign::Negative,
+        },
+        mix::Word::from_value(-130),
+        false,
+      ),
+      (
+        mix::Word {
+          bytes: [32, 32, 0, 0, 0],
+          sign: mix::Sign::Positive,
+        },
+        mix::Word::from_value(1),
+        false,
+      ),
+      (
+        mix::Word {
+          bytes: [32, 31, 63, 63, 63],
+          sign: mix::Sign::Negative,
+        },
+        mix::Word::negative_zero(),
+        false,
+      ),
+      (
+        mix::Word {
+          bytes: [39, 1, 0, 0, 0],
+          sign: mix::Sign::Positive,
+        },
+        mix::Word {
+          bytes: [0, 0, 0, 0, 0],
+          sign: mix::Sign::Positive,
+        },
+        true,
+      ),
+    ];
+
+    for (acc_before, expected_acc, expected_ov) in &tests {
+      let mut computer = Computer::new();
+      computer.accumulator = *acc_before;
+
+      let instruction = mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 7,
+        operation: mix::op_codes::FIX,
+      };
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
+
+      assert_eq!(computer.accumulator, *expected_acc);
+      assert_eq!(computer.overflow, *expected_ov);
+    }
+  }
+}
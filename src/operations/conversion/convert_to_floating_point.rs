@@ -0,0 +1,96 @@
+use crate::computer::{Computer, MixFault};
+
+use crate::operations::arithmetic::floating_point;
+use crate::operations::Operation;
+
+#[derive(Default)]
+pub struct ConvertToFloatingPoint {}
+
+impl ConvertToFloatingPoint {
+  pub fn new() -> ConvertToFloatingPoint {
+    ConvertToFloatingPoint {}
+  }
+}
+
+impl Operation for ConvertToFloatingPoint {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let digits = floating_point::FRACTION_BYTES;
+    let value = computer.accumulator.value_in(byte_size) as i128;
+
+    // the integer has 5 bytes but the fraction only 4, so this may round
+    let (result, overflow) = floating_point::normalize(
+      value * floating_point::power(digits, byte_size),
+      digits,
+      floating_point::excess(byte_size),
+      byte_size,
+    );
+    computer.accumulator = result;
+    if overflow {
+      computer.overflow = true;
+    }
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    3
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::mix;
+
+  #[test]
+  fn test_flot() {
+    let tests = [
+      (mix::Word::from_value(0), mix::Word::zero()),
+      (
+        mix::Word::from_value(1),
+        mix::Word {
+          bytes: [33, 1, 0, 0, 0],
+          sign: mix::Sign::Positive,
+        },
+      ),
+      (
+        mix::Word::from_value(-130),
+        mix::Word {
+          bytes: [34, 2, 2, 0, 0],
+          sign: mix::Sign::Negative,
+        },
+      ),
+      (
+        mix::Word {
+          bytes: [1, 2, 3, 4, 32],
+          sign: mix::Sign::Positive,
+        },
+        mix::Word {
+          bytes: [37, 1, 2, 3, 5],
+          sign: mix::Sign::Positive,
+        },
+      ),
+    ];
+
+    for (acc_before, expected_acc) in &tests {
+      let mut computer = Computer::new();
+      computer.accumulator = *acc_before;
+
+      let instruction = mix::Instruction {
+        address: mix::Address::zero(),
+        index_specification: 0,
+        modification: 6,
+        operation: mix::op_codes::FLOT,
+      };
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
+
+      assert_eq!(computer.accumulator, *expected_acc);
+      assert_eq!(computer.overflow, false);
+    }
+  }
+}
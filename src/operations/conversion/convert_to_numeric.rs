@@ -1,34 +1,45 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use crate::mix;
 use crate::operations::Operation;
 
-pub struct ConvertToNumeric {}
+pub struct ConvertToNumeric<'a> {
+  instruction: &'a mix::Instruction,
+}
 
-impl ConvertToNumeric {
-  pub fn new() -> ConvertToNumeric {
-    ConvertToNumeric {}
+impl<'a> ConvertToNumeric<'a> {
+  pub fn new(instruction: &'a mix::Instruction) -> ConvertToNumeric<'a> {
+    ConvertToNumeric { instruction }
   }
 }
 
-impl Operation for ConvertToNumeric {
-  fn execute(&self, computer: &mut Computer) -> () {
+impl<'a> Operation for ConvertToNumeric<'a> {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let extension = computer.read_extension(self.instruction)?;
+
     let mut result = 0;
     for i in 0..5 {
       let acc_b = computer.accumulator.bytes[5 - 1 - i];
-      let ext_b = computer.extension.read().bytes[5 - 1 - i];
+      let ext_b = extension.bytes[5 - 1 - i];
 
       result += (acc_b % 10) as isize * 10isize.pow((5 + i) as u32);
       result += (ext_b % 10) as isize * 10isize.pow(i as u32);
     }
 
     let sign = computer.accumulator.sign;
-    if mix::Word::fits_in_word(result) {
-      computer.accumulator = mix::Word::from_value(result);
+    if mix::Word::fits_in(result, byte_size) {
+      computer.accumulator = mix::Word::from_value_in(result, byte_size);
     } else {
       computer.overflow = true;
-      computer.accumulator = mix::Word::from_value_with_overflow(result);
+      computer.accumulator = mix::Word::from_value_with_overflow_in(result, byte_size);
     }
     computer.accumulator.sign = sign;
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    10
   }
 }
@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Access, Computer, MixFault};
 
 use super::Operation;
 
@@ -16,18 +16,53 @@ impl<'a> Io<'a> {
 }
 
 impl<'a> Operation for Io<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction);
-    let device = &computer.io_devices[self.instruction.modification as usize];
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let address = computer.get_indexed_address_value(self.instruction)?;
+    let deadline = computer.deadline();
+    let ready_at = {
+      let device = computer.get_io_device(self.instruction)?;
+      if !device.wait_ready_until(deadline) {
+        return Err(computer.wall_clock_exceeded(self.instruction));
+      }
+      device.check_fault()?;
+      device.ready_at()
+    };
 
-    device.wait_ready();
-    device.set_busy();
+    // the machine waits for a busy device to finish
+    computer.clock = computer.clock.max(ready_at);
+    let device = computer.get_io_device(self.instruction)?;
+
+    if self.instruction.operation != mix::op_codes::IOC {
+      if address < 0 {
+        // devices can't reach the control state memory
+        return Err(computer.address_out_of_range(self.instruction, address));
+      }
+      computer.check_memory_range(self.instruction, address, device.block_size)?;
+
+      // another device might be using the same memory
+      let access = if self.instruction.operation == mix::op_codes::IN {
+        Access::Write
+      } else {
+        Access::Read
+      };
+      for buffer_address in address..address + device.block_size as isize {
+        computer.check_buffers(self.instruction, buffer_address, access)?;
+      }
+    }
+
+    device.set_busy(self.instruction.operation, address, computer.clock);
     device
       .send(io::IoMessage {
         operation: self.instruction.operation,
         address,
+        program_counter: computer.program_counter,
+        instruction: *self.instruction,
+      })
+      .map_err(|_| MixFault::Device {
+        program_counter: computer.program_counter,
+        instruction: *self.instruction,
+        message: String::from("device has stopped"),
       })
-      .unwrap();
   }
 }
 
@@ -35,6 +70,8 @@ impl<'a> Operation for Io<'a> {
 mod tests {
   use super::*;
 
+  use crate::computer::load;
+
   #[test]
   fn test_tape_roundtrip() {
     let mut computer = Computer::new();
@@ -76,10 +113,59 @@ mod tests {
       },
     ];
 
-    for (i, instruction) in instructions.iter().enumerate() {
-      computer.memory[i].write(mix::Word::from_instruction(instruction));
+    load(&mut computer, &instructions);
+    computer.start().unwrap();
+
+    for i in 0..100 {
+      assert_eq!(
+        computer.memory[2000 + i].read(),
+        mix::Word::from_value(i as isize)
+      );
+    }
+  }
+
+  #[test]
+  fn test_synchronous_io() {
+    let mut computer = Computer::with_io_mode(io::IoMode::Synchronous);
+
+    for i in 0..100 {
+      computer.memory[1000 + i].write(mix::Word::from_value(i as isize));
+    }
+
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 6,
+        operation: mix::op_codes::OUT,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(-1),
+        index_specification: 0,
+        modification: 6,
+        operation: mix::op_codes::IOC,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(2000),
+        index_specification: 0,
+        modification: 6,
+        operation: mix::op_codes::IN,
+      },
+    ];
+
+    // each transfer is done straight away, but the unit stays busy for as
+    // long as the operation takes, and the next one has to wait for it
+    for instruction in instructions.iter() {
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
+      let device = &computer.io_devices[6];
+      assert!(device.busy(computer.clock));
+      assert!(!device.busy(device.ready_at()));
     }
-    computer.start();
+    assert_eq!(computer.clock, 5000 + 10000);
 
     for i in 0..100 {
       assert_eq!(
@@ -115,7 +201,11 @@ mod tests {
     ];
 
     for instruction in instructions.iter() {
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
     }
 
     // TODO: replace this with a JBUS when it's implemented
@@ -130,4 +220,81 @@ mod tests {
       );
     }
   }
+
+  #[test]
+  fn test_buffer_in_use() {
+    let mut computer = Computer::with_io_mode(io::IoMode::Synchronous);
+    let instructions = [
+      mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 4,
+        operation: mix::op_codes::OUT,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1050),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::LDA,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1050),
+        index_specification: 0,
+        modification: mix::field_spec(0, 5),
+        operation: mix::op_codes::STA,
+      },
+    ];
+    let sharing = [
+      mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 4,
+        operation: mix::op_codes::OUT,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(950),
+        index_specification: 0,
+        modification: 5,
+        operation: mix::op_codes::IN,
+      },
+      mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 5,
+        operation: mix::op_codes::OUT,
+      },
+    ];
+    load(&mut computer, &[instructions, sharing].concat());
+
+    // reading what's being written out is fine, but changing it isn't
+    computer.fetch_decode_execute().unwrap();
+    computer.fetch_decode_execute().unwrap();
+    assert_eq!(
+      computer.fetch_decode_execute(),
+      Err(MixFault::BufferInUse {
+        program_counter: 2,
+        instruction: instructions[2],
+        address: 1050,
+        unit: 4,
+      })
+    );
+
+    // once the tape has finished, the buffer can be used again
+    computer.clock = computer.io_devices[4].ready_at();
+    computer.fetch_decode_execute().unwrap();
+
+    // other devices can write out the same buffer, but not read into it
+    computer.fetch_decode_execute().unwrap();
+    assert_eq!(
+      computer.fetch_decode_execute(),
+      Err(MixFault::BufferInUse {
+        program_counter: 4,
+        instruction: sharing[1],
+        address: 1000,
+        unit: 4,
+      })
+    );
+    computer.program_counter = 5;
+    computer.fetch_decode_execute().unwrap();
+  }
 }
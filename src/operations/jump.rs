@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use super::Operation;
 use crate::mix;
@@ -14,8 +14,8 @@ impl<'a> Jump<'a> {
 }
 
 impl<'a> Operation for Jump<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction) as usize;
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let address = computer.get_indexed_address_value(self.instruction)?;
 
     match self.instruction.modification {
       0 => jump(address, computer),                    // JMP
@@ -28,11 +28,10 @@ impl<'a> Operation for Jump<'a> {
       7 => jump_on_not_comparison(address, computer, mix::Comparison::Less), // JGE
       8 => jump_on_not_comparison(address, computer, mix::Comparison::Equal), // JNE
       9 => jump_on_not_comparison(address, computer, mix::Comparison::Greater), // JLE
-      _ => panic!(
-        "unknown modification for jump operation: {}",
-        self.instruction.modification
-      ),
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     };
+
+    Ok(())
   }
 
   fn should_increment_program_counter(&self) -> bool {
@@ -51,20 +50,20 @@ impl<'a> RegisterJump<'a> {
 }
 
 impl<'a> Operation for RegisterJump<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction) as usize;
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let address = computer.get_indexed_address_value(self.instruction)?;
 
     let value = match self.instruction.operation {
-      mix::op_codes::JAN => computer.accumulator.value(),
-      mix::op_codes::JXN => computer.extension.read().value(),
+      mix::op_codes::JAN => computer.accumulator.value_in(byte_size),
+      mix::op_codes::JXN => computer
+        .read_extension(self.instruction)?
+        .value_in(byte_size),
       mix::op_codes::J1N...mix::op_codes::J6N => {
         let index = (self.instruction.operation - mix::op_codes::J1N) as usize;
-        computer.indexes[index].value()
+        computer.indexes[index].value_in(byte_size)
       }
-      _ => panic!(
-        "unknown opcode for jump operation: {}",
-        self.instruction.operation
-      ),
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     };
 
     match self.instruction.modification {
@@ -74,11 +73,12 @@ impl<'a> Operation for RegisterJump<'a> {
       3 => conditional_jump(address, computer, value >= 0), // JANN
       4 => conditional_jump(address, computer, value != 0), // JANZ
       5 => conditional_jump(address, computer, value <= 0), // JANP
-      _ => panic!(
-        "unknown modification for jump operation: {}",
-        self.instruction.modification
-      ),
+      6 => conditional_jump(address, computer, value % 2 == 0), // JAE
+      7 => conditional_jump(address, computer, value % 2 != 0), // JAO
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     };
+
+    Ok(())
   }
 
   fn should_increment_program_counter(&self) -> bool {
@@ -97,22 +97,22 @@ impl<'a> IoJump<'a> {
 }
 
 impl<'a> Operation for IoJump<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction) as usize;
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let address = computer.get_indexed_address_value(self.instruction)?;
 
     let busy = {
-      let device = &computer.io_devices[self.instruction.modification as usize];
-      device.busy()
+      let device = computer.get_io_device(self.instruction)?;
+      device.check_fault()?;
+      device.busy(computer.clock)
     };
 
     match self.instruction.operation {
       mix::op_codes::JBUS => conditional_jump(address, computer, busy),
       mix::op_codes::JRED => conditional_jump(address, computer, !busy),
-      _ => panic!(
-        "unknown opcode for jump operation: {}",
-        self.instruction.operation
-      ),
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     };
+
+    Ok(())
   }
 
   fn should_increment_program_counter(&self) -> bool {
@@ -120,16 +120,18 @@ impl<'a> Operation for IoJump<'a> {
   }
 }
 
-fn jump(address: usize, computer: &mut Computer) -> () {
-  computer.jump_address = mix::Address::from_value(computer.program_counter as isize + 1);
-  computer.program_counter = address;
+fn jump(address: isize, computer: &mut Computer) -> () {
+  computer.jump_address =
+    mix::Address::from_value_in(computer.program_counter + 1, computer.byte_size);
+  jump_save_j(address, computer);
 }
 
-fn jump_save_j(address: usize, computer: &mut Computer) -> () {
+fn jump_save_j(address: isize, computer: &mut Computer) -> () {
   computer.program_counter = address;
+  computer.record_jump();
 }
 
-fn conditional_jump(address: usize, computer: &mut Computer, condition: bool) -> () {
+fn conditional_jump(address: isize, computer: &mut Computer, condition: bool) -> () {
   if condition {
     jump(address, computer);
   } else {
@@ -137,17 +139,17 @@ fn conditional_jump(address: usize, computer: &mut Computer, condition: bool) ->
   }
 }
 
-fn jump_on_overflow(address: usize, computer: &mut Computer, overflow: bool) -> () {
+fn jump_on_overflow(address: isize, computer: &mut Computer, overflow: bool) -> () {
   conditional_jump(address, computer, computer.overflow == overflow);
   computer.overflow = false;
 }
 
-fn jump_on_comparison(address: usize, computer: &mut Computer, comparison: mix::Comparison) -> () {
+fn jump_on_comparison(address: isize, computer: &mut Computer, comparison: mix::Comparison) -> () {
   conditional_jump(address, computer, computer.comparison == comparison);
 }
 
 fn jump_on_not_comparison(
-  address: usize,
+  address: isize,
   computer: &mut Computer,
   comparison: mix::Comparison,
 ) -> () {
@@ -170,7 +172,7 @@ mod tests {
     };
     computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-    computer.fetch_decode_execute();
+    computer.fetch_decode_execute().unwrap();
 
     assert_eq!(computer.program_counter, 1000);
     assert_eq!(computer.jump_address, mix::Address::from_value(101));
@@ -189,7 +191,7 @@ mod tests {
     };
     computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-    computer.fetch_decode_execute();
+    computer.fetch_decode_execute().unwrap();
 
     assert_eq!(computer.program_counter, 1000);
     assert_eq!(computer.jump_address, mix::Address::from_value(50));
@@ -212,7 +214,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
       assert_eq!(computer.overflow, false);
@@ -236,7 +238,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
       assert_eq!(computer.overflow, false);
@@ -264,7 +266,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -291,7 +293,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -318,7 +320,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -345,7 +347,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -372,7 +374,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -399,7 +401,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -426,7 +428,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -453,7 +455,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -480,7 +482,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -507,7 +509,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -534,7 +536,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -561,7 +563,61 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
+
+      assert_eq!(computer.program_counter, *expected_pc);
+    }
+  }
+
+  #[test]
+  fn test_jae() {
+    let tests = [
+      (mix::Word::from_value(5000), 1000),
+      (mix::Word::from_value(-5001), 101),
+      (mix::Word::zero(), 1000),
+    ];
+
+    for (acc_before, expected_pc) in &tests {
+      let mut computer = Computer::new();
+      computer.program_counter = 100;
+      computer.accumulator = *acc_before;
+
+      let instruction = mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 6,
+        operation: mix::op_codes::JAE,
+      };
+      computer.memory[100].write(mix::Word::from_instruction(&instruction));
+
+      computer.fetch_decode_execute().unwrap();
+
+      assert_eq!(computer.program_counter, *expected_pc);
+    }
+  }
+
+  #[test]
+  fn test_jao() {
+    let tests = [
+      (mix::Word::from_value(5000), 101),
+      (mix::Word::from_value(-5001), 1000),
+      (mix::Word::zero(), 101),
+    ];
+
+    for (acc_before, expected_pc) in &tests {
+      let mut computer = Computer::new();
+      computer.program_counter = 100;
+      computer.accumulator = *acc_before;
+
+      let instruction = mix::Instruction {
+        address: mix::Address::from_value(1000),
+        index_specification: 0,
+        modification: 7,
+        operation: mix::op_codes::JAO,
+      };
+      computer.memory[100].write(mix::Word::from_instruction(&instruction));
+
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -588,7 +644,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -615,7 +671,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -642,7 +698,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -669,7 +725,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -696,7 +752,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -723,7 +779,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -750,7 +806,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -777,7 +833,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -804,7 +860,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -831,7 +887,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -858,7 +914,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -885,7 +941,7 @@ mod tests {
       };
       computer.memory[100].write(mix::Word::from_instruction(&instruction));
 
-      computer.fetch_decode_execute();
+      computer.fetch_decode_execute().unwrap();
 
       assert_eq!(computer.program_counter, *expected_pc);
     }
@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use super::Operation;
 use crate::mix;
@@ -14,10 +14,10 @@ impl<'a> Load<'a> {
 }
 
 impl<'a> Operation for Load<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let address = computer.get_indexed_address_value(self.instruction) as usize;
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let address = computer.get_memory_address(self.instruction)?;
 
-    let word = computer.memory[address].read();
+    let word = computer.read_memory(self.instruction, address)?;
     let mut word = word.apply_field_spec(self.instruction.modification);
 
     if mix::op_codes::LDAN <= self.instruction.operation
@@ -31,7 +31,7 @@ impl<'a> Operation for Load<'a> {
         computer.accumulator = word;
       }
       mix::op_codes::LDX | mix::op_codes::LDXN => {
-        computer.extension.write(word);
+        computer.write_extension(self.instruction, word)?;
       }
       mix::op_codes::LD1...mix::op_codes::LD6 => {
         let index = (self.instruction.operation - mix::op_codes::LD1) as usize;
@@ -41,8 +41,14 @@ impl<'a> Operation for Load<'a> {
         let index = (self.instruction.operation - mix::op_codes::LD1N) as usize;
         computer.indexes[index] = word.cast_to_address();
       }
-      _ => panic!("unknown load operation {}", self.instruction.operation),
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     }
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    2
   }
 }
 
@@ -143,7 +149,11 @@ mod tests {
         sign: mix::Sign::Negative,
       });
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc);
     }
@@ -242,7 +252,11 @@ mod tests {
         sign: mix::Sign::Negative,
       });
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.extension.read(), *expected_ext);
     }
@@ -338,7 +352,11 @@ mod tests {
         sign: mix::Sign::Negative,
       });
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.indexes[(index - 1) as usize], *expected_reg);
     }
@@ -440,7 +458,11 @@ mod tests {
         sign: mix::Sign::Negative,
       });
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc);
     }
@@ -542,7 +564,11 @@ mod tests {
         sign: mix::Sign::Negative,
       });
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.extension.read(), *expected_ext);
     }
@@ -638,7 +664,11 @@ mod tests {
         sign: mix::Sign::Negative,
       });
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.indexes[(index - 1) as usize], *expected_reg);
     }
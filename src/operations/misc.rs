@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{self, Computer, MixFault};
 
 use super::Operation;
 use crate::mix;
@@ -12,7 +12,9 @@ impl NoOp {
 }
 
 impl Operation for NoOp {
-  fn execute(&self, _computer: &mut Computer) -> () {}
+  fn execute(&self, _computer: &mut Computer) -> Result<(), MixFault> {
+    Ok(())
+  }
 }
 
 pub struct Halt<'a> {
@@ -26,10 +28,52 @@ impl<'a> Halt<'a> {
 }
 
 impl<'a> Operation for Halt<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    if self.instruction.address.value() != 0 {
-      panic!("HLT with code {}", self.instruction.address.value());
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    if self.instruction.address.value_in(byte_size) != 0 {
+      return Err(MixFault::HaltWithCode {
+        program_counter: computer.program_counter,
+        instruction: *self.instruction,
+        code: self.instruction.address.value_in(byte_size),
+      });
     }
     computer.running = false;
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    10
+  }
+}
+
+pub struct Interrupt<'a> {
+  instruction: &'a mix::Instruction,
+}
+
+impl<'a> Interrupt<'a> {
+  pub fn new(instruction: &'a mix::Instruction) -> Interrupt<'a> {
+    Interrupt { instruction }
+  }
+}
+
+impl<'a> Operation for Interrupt<'a> {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    if computer.interrupts.is_none() {
+      return Err(computer.invalid_instruction(self.instruction));
+    }
+
+    if computer.in_control_state() {
+      computer.return_from_interrupt();
+    } else {
+      let next = computer.program_counter + 1;
+      computer.interrupt(computer::INT_INTERRUPT, next);
+    }
+
+    Ok(())
+  }
+
+  fn should_increment_program_counter(&self) -> bool {
+    false
   }
 }
@@ -10,24 +10,33 @@ mod mov;
 mod shift;
 mod storing;
 
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 pub use address_transfer::{Enter, Increase};
-pub use arithmetic::{Addition, Division, Multiplication};
-pub use comparison::Compare;
-pub use conversion::{ConvertToCharacters, ConvertToNumeric};
+pub use arithmetic::{
+  Addition, Division, FloatingAddition, FloatingDivision, FloatingMultiplication, Multiplication,
+};
+pub use comparison::{Compare, FloatingCompare};
+pub use conversion::{
+  ConvertToCharacters, ConvertToFixedPoint, ConvertToFloatingPoint, ConvertToNumeric,
+};
 pub use io::Io;
 pub use jump::{IoJump, Jump, RegisterJump};
 pub use loading::Load;
-pub use misc::{Halt, NoOp};
+pub use misc::{Halt, Interrupt, NoOp};
 pub use mov::Move;
 pub use shift::Shift;
 pub use storing::Store;
 
 pub trait Operation {
-  fn execute(&self, computer: &mut Computer) -> ();
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault>;
 
   fn should_increment_program_counter(&self) -> bool {
     true
   }
+
+  // execution time in units of u - most operations take 1u
+  fn time(&self) -> usize {
+    1
+  }
 }
@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use super::Operation;
 use crate::mix;
@@ -14,16 +14,27 @@ impl<'a> Move<'a> {
 }
 
 impl<'a> Operation for Move<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let src = computer.get_indexed_address_value(self.instruction) as usize;
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let src = computer.get_indexed_address_value(self.instruction)?;
 
-    let dest = computer.indexes[0].value() as usize;
+    let dest = computer.indexes[0].value_in(byte_size);
     let num = self.instruction.modification as usize;
 
-    for i in 0..num {
-      computer.memory[dest + i].write(computer.memory[src + i].read());
+    computer.check_memory_range(self.instruction, src, num)?;
+    computer.check_memory_range(self.instruction, dest, num)?;
+
+    for i in 0..num as isize {
+      let word = computer.read_memory(self.instruction, src + i)?;
+      computer.write_memory(self.instruction, dest + i, word)?;
     }
     
-    computer.indexes[0] = mix::Address::from_value((dest + num) as isize);
+    computer.indexes[0] = mix::Address::from_value_in(dest + num as isize, byte_size);
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    1 + 2 * self.instruction.modification as usize
   }
 }
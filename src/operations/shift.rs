@@ -1,8 +1,11 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use super::Operation;
 use crate::mix;
 
+// a binary MIX word has 30 bits of magnitude
+const WORD_BITS: isize = 30;
+
 pub struct Shift<'a> {
   instruction: &'a mix::Instruction,
 }
@@ -14,11 +17,12 @@ impl<'a> Shift<'a> {
 }
 
 impl<'a> Operation for Shift<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
-    let mut m = computer.get_indexed_address_value(self.instruction);
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
+    let byte_size = computer.byte_size;
+    let mut m = computer.get_indexed_address_value(self.instruction)?;
 
     if self.instruction.modification % 2 == 1 {
-      // SRA, SRAX, SRC
+      // SRA, SRAX, SRC, SRB
       m = -m;
     }
 
@@ -29,12 +33,13 @@ impl<'a> Operation for Shift<'a> {
         computer.accumulator.bytes = new_bytes;
       }
       2 | 3 => {
+        let ext = computer.read_extension(self.instruction)?;
         let mut bytes = [0; 10];
         for i in 0..5 {
           bytes[i] = computer.accumulator.bytes[i];
         }
         for i in 0..5 {
-          bytes[5 + i] = computer.extension.read().bytes[i];
+          bytes[5 + i] = ext.bytes[i];
         }
         let new_bytes = shift_all(bytes, m);
 
@@ -47,20 +52,22 @@ impl<'a> Operation for Shift<'a> {
           ext_bytes[i] = new_bytes[5 + i];
         }
         computer.accumulator.bytes = acc_bytes;
-        let ext = computer.extension.read();
-        computer.extension.write(mix::Word {
-          sign: ext.sign,
-          bytes: ext_bytes,
-        });
+        computer.write_extension(
+          self.instruction,
+          mix::Word {
+            sign: ext.sign,
+            bytes: ext_bytes,
+          },
+        )?;
       }
       4 | 5 => {
-
+        let ext = computer.read_extension(self.instruction)?;
         let mut bytes = [0; 10];
         for i in 0..5 {
           bytes[i] = computer.accumulator.bytes[i];
         }
         for i in 0..5 {
-          bytes[5 + i] = computer.extension.read().bytes[i];
+          bytes[5 + i] = ext.bytes[i];
         }
         let new_bytes = shift_circle(bytes, m);
 
@@ -73,17 +80,42 @@ impl<'a> Operation for Shift<'a> {
           ext_bytes[i] = new_bytes[5 + i];
         }
         computer.accumulator.bytes = acc_bytes;
-        let ext = computer.extension.read();
-        computer.extension.write(mix::Word {
-          sign: ext.sign,
-          bytes: ext_bytes,
-        });
+        computer.write_extension(
+          self.instruction,
+          mix::Word {
+            sign: ext.sign,
+            bytes: ext_bytes,
+          },
+        )?;
       }
-      _ => panic!(
-        "unknown modification for shift operation: {}",
-        self.instruction.modification
-      ),
+      6 | 7 => {
+        if byte_size != mix::BINARY_BYTE_SIZE {
+          // binary shifts only make sense on a binary MIX
+          return Err(computer.invalid_instruction(self.instruction));
+        }
+
+        // the signs are left alone, and the magnitudes shifted as one 60-bit number
+        let ext = computer.read_extension(self.instruction)?;
+        let bits = (computer.accumulator.value_in(byte_size).abs() << WORD_BITS)
+          | ext.value_in(byte_size).abs();
+        let new_bits = shift_binary(bits, m);
+
+        let sign = computer.accumulator.sign;
+        computer.accumulator = mix::Word::from_value_in(new_bits >> WORD_BITS, byte_size);
+        computer.accumulator.sign = sign;
+
+        let mut new_ext = mix::Word::from_value_in(new_bits & ((1 << WORD_BITS) - 1), byte_size);
+        new_ext.sign = ext.sign;
+        computer.write_extension(self.instruction, new_ext)?;
+      }
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     };
+
+    Ok(())
+  }
+
+  fn time(&self) -> usize {
+    2
   }
 }
 
@@ -137,6 +169,18 @@ fn shift_circle(bytes: [u8; 10], m: isize) -> [u8; 10] {
   new_bytes
 }
 
+fn shift_binary(bits: isize, m: isize) -> isize {
+  let size = 2 * WORD_BITS;
+
+  if m >= size || m <= -size {
+    0
+  } else if m >= 0 {
+    (bits << m) & ((1 << size) - 1)
+  } else {
+    bits >> -m
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -238,7 +282,74 @@ mod tests {
 
     for (instruction, expected_acc, expected_ext) in &tests {
       eprintln!("yo");
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
+
+      assert_eq!(computer.accumulator, *expected_acc);
+      assert_eq!(computer.extension.read(), *expected_ext);
+    }
+  }
+
+  #[test]
+  fn test_binary_shifts() {
+    let mut computer = Computer::new();
+    computer.accumulator = mix::Word {
+      bytes: [0, 0, 0, 0, 1],
+      sign: mix::Sign::Positive,
+    };
+    computer.extension.write(mix::Word {
+      bytes: [32, 0, 0, 0, 0],
+      sign: mix::Sign::Negative,
+    });
+
+    let tests = [
+      (
+        mix::Instruction {
+          address: mix::Address::from_value(1),
+          index_specification: 0,
+          modification: 7,
+          operation: mix::op_codes::SRB,
+        },
+        mix::Word::zero(),
+        mix::Word {
+          bytes: [48, 0, 0, 0, 0],
+          sign: mix::Sign::Negative,
+        },
+      ),
+      (
+        mix::Instruction {
+          address: mix::Address::from_value(3),
+          index_specification: 0,
+          modification: 6,
+          operation: mix::op_codes::SLB,
+        },
+        mix::Word {
+          bytes: [0, 0, 0, 0, 6],
+          sign: mix::Sign::Positive,
+        },
+        mix::Word::negative_zero(),
+      ),
+      (
+        mix::Instruction {
+          address: mix::Address::from_value(60),
+          index_specification: 0,
+          modification: 6,
+          operation: mix::op_codes::SLB,
+        },
+        mix::Word::zero(),
+        mix::Word::negative_zero(),
+      ),
+    ];
+
+    for (instruction, expected_acc, expected_ext) in &tests {
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.accumulator, *expected_acc);
       assert_eq!(computer.extension.read(), *expected_ext);
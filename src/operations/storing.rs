@@ -1,4 +1,4 @@
-use crate::computer::Computer;
+use crate::computer::{Computer, MixFault};
 
 use super::Operation;
 use crate::mix;
@@ -14,20 +14,20 @@ impl<'a> Store<'a> {
 }
 
 impl<'a> Operation for Store<'a> {
-  fn execute(&self, computer: &mut Computer) -> () {
+  fn execute(&self, computer: &mut Computer) -> Result<(), MixFault> {
     let register = match self.instruction.operation {
       mix::op_codes::STA => computer.accumulator,
-      mix::op_codes::STX => computer.extension.read(),
+      mix::op_codes::STX => computer.read_extension(self.instruction)?,
       mix::op_codes::ST1...mix::op_codes::ST6 => {
         let index = (self.instruction.operation - mix::op_codes::ST1) as usize;
         computer.indexes[index].cast_to_word()
       }
       mix::op_codes::STJ => computer.jump_address.cast_to_word(),
       mix::op_codes::STZ => mix::Word::zero(),
-      _ => panic!("unknown store operation {}", self.instruction.operation),
+      _ => return Err(computer.invalid_instruction(self.instruction)),
     };
 
-    let address = computer.get_indexed_address_value(self.instruction);
+    let address = computer.get_memory_address(self.instruction)?;
     let (left, right) = mix::decode_field_spec(self.instruction.modification);
     let mut left = left;
 
@@ -38,7 +38,7 @@ impl<'a> Operation for Store<'a> {
     }
 
     let bytes = get_bytes_to_store(&register, num_bytes);
-    let mut word = computer.memory[address as usize].read();
+    let mut word = computer.read_memory_for_store(self.instruction, address)?;
     if left == 0 {
       word.sign = register.sign;
       left += 1;
@@ -48,7 +48,11 @@ impl<'a> Operation for Store<'a> {
       word.bytes[left as usize + i - 1] = value;
     }
 
-    computer.memory[address as usize].write(word);
+    computer.write_memory(self.instruction, address, word)
+  }
+
+  fn time(&self) -> usize {
+    2
   }
 }
 
@@ -151,7 +155,11 @@ mod tests {
         sign: mix::Sign::Positive,
       };
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.memory[2000].read(), *expected_mem);
     }
@@ -245,7 +253,11 @@ mod tests {
         sign: mix::Sign::Positive,
       });
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.memory[2000].read(), *expected_mem);
     }
@@ -345,7 +357,11 @@ mod tests {
         sign: mix::Sign::Positive,
       };
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.memory[2000].read(), *expected_mem);
     }
@@ -439,7 +455,11 @@ mod tests {
         sign: mix::Sign::Positive,
       };
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.memory[2000].read(), *expected_mem);
     }
@@ -533,7 +553,11 @@ mod tests {
         sign: mix::Sign::Positive,
       };
 
-      instruction.decode().execute(&mut computer);
+      instruction
+        .decode()
+        .unwrap()
+        .execute(&mut computer)
+        .unwrap();
 
       assert_eq!(computer.memory[2000].read(), *expected_mem);
     }